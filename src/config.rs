@@ -0,0 +1,52 @@
+//! Loading the optional YAML files that tune how results are marked and scored
+
+use serde::de::DeserializeOwned;
+use std::{error::Error, fmt, fs, io, path::Path, path::PathBuf};
+
+/// Why a config file couldn't be loaded
+#[derive(Debug)]
+pub enum ConfigError {
+    Read {
+        path: PathBuf,
+        error: io::Error,
+    },
+    Invalid {
+        path: PathBuf,
+        error: serde_yaml::Error,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read { path, error } => {
+                write!(f, "failed to read {}: {}", path.display(), error)
+            }
+            Self::Invalid { path, error } => write!(f, "invalid {}: {}", path.display(), error),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Read { error, .. } => Some(error),
+            Self::Invalid { error, .. } => Some(error),
+        }
+    }
+}
+
+/// Uses the default if there's no file at `path`
+pub(crate) fn load_yaml<T: DeserializeOwned + Default>(path: &Path) -> Result<T, ConfigError> {
+    match fs::read_to_string(path) {
+        Ok(contents) => serde_yaml::from_str(&contents).map_err(|error| ConfigError::Invalid {
+            path: path.to_owned(),
+            error,
+        }),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+        Err(error) => Err(ConfigError::Read {
+            path: path.to_owned(),
+            error,
+        }),
+    }
+}
@@ -0,0 +1,125 @@
+//! Explanations of what each HTTP and HTTPS result means, and how to fix it, for teams using nginx
+
+use crate::status::{HttpProtocol, ProtocolResult};
+use crate::teams::{Endpoint, Expected};
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
+pub struct Guidance {
+    pub explanation: String,
+    /// Commands or config that usually fix the problem
+    pub fix: Option<String>,
+}
+
+impl Guidance {
+    fn new(explanation: &str, fix: Option<String>) -> Self {
+        Self {
+            explanation: explanation.to_owned(),
+            fix,
+        }
+    }
+}
+
+fn redirect_fix(endpoint: &Endpoint) -> String {
+    match &endpoint.expected {
+        Expected::Serve => format!(
+            "server {{\n    listen 80;\n    server_name {};\n    return 301 https://$host$request_uri;\n}}",
+            endpoint.domain
+        ),
+        Expected::Redirect { to } => format!(
+            "server {{\n    listen 80;\n    listen 443 ssl;\n    server_name {};\n    return 301 {};\n}}",
+            endpoint.domain, to
+        ),
+    }
+}
+
+/// `None` if the result is already correct
+pub fn protocol_guidance(
+    result: &ProtocolResult,
+    protocol: HttpProtocol,
+    endpoint: &Endpoint,
+) -> Option<Guidance> {
+    let certbot = format!("sudo certbot --nginx -d {}", endpoint.domain);
+    Some(match (result, protocol, &endpoint.expected) {
+        (ProtocolResult::Ok(_), HttpProtocol::Https, Expected::Serve)
+        | (ProtocolResult::CorrectRedirect(_), _, _) => return None,
+        (ProtocolResult::Ok(_), HttpProtocol::Http, Expected::Serve) => Guidance::new(
+            "The site is served over plain HTTP instead of redirecting to HTTPS, so visitors who \
+             type the domain aren't protected.",
+            Some(redirect_fix(endpoint)),
+        ),
+        (ProtocolResult::Ok(_), _, Expected::Redirect { .. }) => Guidance::new(
+            "This domain still serves content, rather than redirecting to where the site has moved.",
+            Some(redirect_fix(endpoint)),
+        ),
+        (ProtocolResult::NginxDefaultPage(_), _, _) => Guidance::new(
+            "nginx's welcome page is being served, so your site's config isn't being used.",
+            Some(format!(
+                "# Set `server_name {};` in your site's config, then\n\
+                 sudo ln -s /etc/nginx/sites-available/<site> /etc/nginx/sites-enabled/\n\
+                 sudo rm /etc/nginx/sites-enabled/default\n\
+                 sudo nginx -t && sudo systemctl reload nginx",
+                endpoint.domain
+            )),
+        ),
+        (ProtocolResult::MixedContent(..), _, _) => Guidance::new(
+            "The page loads scripts, styles, images or frames over http://, which browsers block \
+             on HTTPS pages.",
+            Some(
+                "# Use https:// or relative URLs for them, or have browsers upgrade them with\n\
+                 add_header Content-Security-Policy \"upgrade-insecure-requests\";"
+                    .to_owned(),
+            ),
+        ),
+        (ProtocolResult::IncorrectRedirect(_), _, _) => Guidance::new(
+            "The redirect goes somewhere other than the same path on the expected HTTPS URL.",
+            Some(redirect_fix(endpoint)),
+        ),
+        (ProtocolResult::UnexpectedResponse(status_code), _, _) => Guidance::new(
+            match status_code {
+                502 | 504 => {
+                    "nginx couldn't reach your app, usually because it isn't running or \
+                     `proxy_pass` points at the wrong port."
+                }
+                404 => "Nothing is served at this path, so check your `root` or routes.",
+                _ => "The server responded with an unexpected status code.",
+            },
+            Some(
+                "sudo systemctl status <your app's service>\n\
+                 sudo tail -n 50 /var/log/nginx/error.log"
+                    .to_owned(),
+            ),
+        ),
+        (ProtocolResult::Timeout(_), _, _) => Guidance::new(
+            "The server didn't respond in time, usually because a firewall is dropping the \
+             connection.",
+            Some("sudo ufw allow 'Nginx Full'\nsudo ss -tlnp".to_owned()),
+        ),
+        (ProtocolResult::UntrustedCertificate(_), _, _) => Guidance::new(
+            "The certificate isn't trusted, e.g. it's self-signed or is missing its intermediate \
+             certificates.",
+            Some(format!(
+                "{}\n# or, with an existing certificate\nssl_certificate /etc/letsencrypt/live/{}/fullchain.pem;",
+                certbot, endpoint.domain
+            )),
+        ),
+        (ProtocolResult::InvalidCertificate(_), _, _) => Guidance::new(
+            "The certificate is for a different domain.",
+            Some(certbot),
+        ),
+        (ProtocolResult::FailedConnect(_), protocol, _) => Guidance::new(
+            match protocol {
+                HttpProtocol::Http => "Nothing accepted the connection on port 80.",
+                HttpProtocol::Https => "Nothing accepted the connection on port 443.",
+            },
+            Some(
+                "sudo systemctl status nginx\n\
+                 # Check your server blocks have `listen 80;` and `listen 443 ssl;`"
+                    .to_owned(),
+            ),
+        ),
+        (ProtocolResult::Error(_), _, _) => {
+            Guidance::new("The request failed for another reason.", None)
+        }
+    })
+}
@@ -0,0 +1,59 @@
+//! State kept between runs in `history.json`, for stats that can't be computed from a single run
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io};
+
+const HISTORY_PATH: &str = "history.json";
+const INVALID_HISTORY_PATH: &str = "history.json.invalid";
+
+#[derive(Serialize, Deserialize)]
+pub struct History {
+    /// When the first run was, which time-to-first-correct is measured from
+    pub started: DateTime<Utc>,
+    /// When each team's HTTP and HTTPS were first both correct
+    pub first_correct: BTreeMap<u8, DateTime<Utc>>,
+}
+
+impl History {
+    /// Starts a new history at `now` if there isn't one yet, or if it's invalid
+    #[must_use]
+    pub fn load(now: DateTime<Utc>) -> Self {
+        let new = Self {
+            started: now,
+            first_correct: BTreeMap::new(),
+        };
+        match fs::read_to_string(HISTORY_PATH) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                // Kept for inspection rather than overwritten by the next save
+                eprintln!(
+                    "Warning: invalid history file {}, moved to {} and starting a new history: {}",
+                    HISTORY_PATH, INVALID_HISTORY_PATH, e
+                );
+                if let Err(e) = fs::rename(HISTORY_PATH, INVALID_HISTORY_PATH) {
+                    eprintln!("Warning: failed to move invalid history file: {}", e);
+                }
+                new
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => new,
+            Err(e) => panic!("failed to read history file {}: {}", HISTORY_PATH, e),
+        }
+    }
+
+    pub fn save(&self) {
+        fs::write(HISTORY_PATH, serde_json::to_string_pretty(self).unwrap())
+            .unwrap_or_else(|e| panic!("failed to write history file {}: {}", HISTORY_PATH, e));
+    }
+
+    /// Keeps the earliest time a team was correct, so later regressions don't reset it
+    pub fn record_correct(&mut self, team_number: u8, timestamp: DateTime<Utc>) {
+        self.first_correct.entry(team_number).or_insert(timestamp);
+    }
+
+    #[must_use]
+    pub fn time_to_first_correct(&self, team_number: u8) -> Option<chrono::Duration> {
+        self.first_correct
+            .get(&team_number)
+            .map(|first_correct| *first_correct - self.started)
+    }
+}
@@ -0,0 +1,56 @@
+//! Checks that each team's project is deployed correctly, and renders the results as status pages.
+//! The binary checks [`teams::TEAMS`] and writes the pages, and other tools can embed the checker
+//! with [`status::Checker`] and [`status::get_results`].
+
+#![warn(clippy::cast_lossless)]
+#![warn(clippy::cast_possible_wrap)]
+#![warn(clippy::default_trait_access)]
+#![warn(clippy::else_if_without_else)]
+#![warn(clippy::empty_enums)]
+#![warn(clippy::empty_line_after_outer_attr)]
+#![warn(clippy::enum_glob_use)]
+#![warn(clippy::equatable_if_let)]
+#![warn(clippy::float_cmp)]
+#![warn(clippy::fn_params_excessive_bools)]
+#![warn(clippy::get_unwrap)]
+#![warn(clippy::inefficient_to_string)]
+#![warn(clippy::integer_division)]
+#![warn(clippy::let_unit_value)]
+#![warn(clippy::linkedlist)]
+#![warn(clippy::lossy_float_literal)]
+#![warn(clippy::macro_use_imports)]
+#![warn(clippy::manual_assert)]
+#![warn(clippy::manual_ok_or)]
+#![warn(clippy::many_single_char_names)]
+#![warn(clippy::map_err_ignore)]
+#![warn(clippy::map_unwrap_or)]
+#![warn(clippy::match_bool)]
+#![warn(clippy::match_same_arms)]
+#![warn(clippy::match_wild_err_arm)]
+#![warn(clippy::match_wildcard_for_single_variants)]
+#![warn(clippy::mem_forget)]
+#![warn(clippy::missing_const_for_fn)]
+#![warn(clippy::must_use_candidate)]
+#![warn(clippy::mut_mut)]
+#![warn(clippy::negative_feature_names)]
+#![warn(non_ascii_idents)]
+#![warn(clippy::option_option)]
+#![warn(clippy::redundant_feature_names)]
+#![warn(clippy::redundant_pub_crate)]
+#![warn(clippy::str_to_string)]
+#![warn(clippy::trait_duplication_in_bounds)]
+#![warn(clippy::unused_async)]
+#![warn(clippy::unused_self)]
+#![warn(clippy::use_self)]
+#![warn(clippy::wildcard_dependencies)]
+#![warn(clippy::wildcard_imports)]
+#![warn(clippy::zero_sized_map_values)]
+
+pub mod config;
+mod guidance;
+pub mod history;
+pub mod policy;
+pub mod render;
+pub mod scoring;
+pub mod status;
+pub mod teams;
@@ -2,7 +2,7 @@
 #![warn(clippy::cast_possible_wrap)]
 #![warn(clippy::default_trait_access)]
 #![warn(clippy::else_if_without_else)]
-#![warn(clippy::empty_enum)]
+#![warn(clippy::empty_enums)]
 #![warn(clippy::empty_line_after_outer_attr)]
 #![warn(clippy::enum_glob_use)]
 #![warn(clippy::equatable_if_let)]
@@ -21,7 +21,6 @@
 #![warn(clippy::map_err_ignore)]
 #![warn(clippy::map_unwrap_or)]
 #![warn(clippy::match_bool)]
-#![warn(clippy::match_on_vec_items)]
 #![warn(clippy::match_same_arms)]
 #![warn(clippy::match_wild_err_arm)]
 #![warn(clippy::match_wildcard_for_single_variants)]
@@ -35,7 +34,6 @@
 #![warn(clippy::redundant_feature_names)]
 #![warn(clippy::redundant_pub_crate)]
 #![warn(clippy::str_to_string)]
-#![warn(clippy::string_to_string)]
 #![warn(clippy::trait_duplication_in_bounds)]
 #![warn(clippy::unused_async)]
 #![warn(clippy::unused_self)]
@@ -44,172 +42,80 @@
 #![warn(clippy::wildcard_imports)]
 #![warn(clippy::zero_sized_map_values)]
 
-mod status;
-mod teams;
-
-use status::{get_results, HttpProtocol, ProtocolResult, TeamResult};
-
-use actix_web::{
-    get,
-    http::{self, header::ContentType},
-    web, App, HttpResponse, HttpServer, Responder,
+use actix_web::web;
+use std::{
+    env, fs,
+    io::{self, IsTerminal},
+    path::Path,
+    process,
 };
-use handlebars::handlebars_helper;
-use serde::Serialize;
-use serde_json::json;
-use std::fs;
-use teams::Team;
 use tokio::sync::RwLock;
+use uob_team_project_progress_tracker::{
+    history::History,
+    policy::Policy,
+    render::{write_results, Format, Renderer, TeamResultTemplate},
+    scoring::Weights,
+    status::{get_results, Checker},
+};
+
+const SCORING_PATH: &str = "config/scoring.yaml";
+const POLICY_PATH: &str = "config/policy.yaml";
+const JOURNEYS_DIR: &str = "journeys";
 
 const STALE_SECONDS: i64 = 60;
 const MAX_STALE_SECONDS: i64 = 60 * 60;
 
-#[derive(Clone, Serialize)]
-#[serde(tag = "type")]
-enum RequestResultResponseTemplate {
-    #[serde(rename = "OK")]
-    Ok { status_code: u16 },
-    #[serde(rename = "Teapot")]
-    Teapot,
-    #[serde(rename = "Redirect")]
-    Redirect { status_code: u16 },
-    #[serde(rename = "Unexpected response")]
-    UnexpectedResponse { status_code: u16 },
-    #[serde(rename = "Timeout")]
-    Timeout,
-    #[serde(rename = "Untrusted certificate")]
-    UntrustedCertificate,
-    #[serde(rename = "Invalid certificate")]
-    InvalidCertificate,
-    #[serde(rename = "Failed to connect")]
-    FailedConnect,
-    #[serde(rename = "Error")]
-    Error,
-}
-
-enum RequestResultStatus {
-    Correct,
-    NearlyCorrect,
-    Incorrect,
-}
+const USAGE: &str =
+    "usage: uob-team-project-progress-tracker [check [--format table|json|csv|markdown]]
 
-impl RequestResultStatus {
-    fn to_bootstrap_class(&self) -> String {
-        match self {
-            Self::Correct => "link-success",
-            Self::NearlyCorrect => "link-warning",
-            Self::Incorrect => "link-danger",
-        }
-        .to_owned()
-    }
+Without a command, writes the pages to upload/ and staff/.
+check (or table) prints the results instead, without writing anything.";
 
-    fn to_alt_text(&self) -> String {
-        match self {
-            Self::Correct => "Correct",
-            Self::NearlyCorrect => "Nearly correct",
-            Self::Incorrect => "Incorrect",
-        }
-        .to_owned()
-    }
-}
-
-#[derive(Clone, Serialize)]
-struct RequestResultTemplate {
-    result: RequestResultResponseTemplate,
-    bootstrap_class: String,
-    alt_text: String,
+enum Command {
+    /// Writes the pages
+    Upload,
+    /// Prints the results
+    Check { format: Format },
 }
 
-impl RequestResultTemplate {
-    fn new(result: RequestResultResponseTemplate, status: RequestResultStatus) -> Self {
-        Self {
-            result,
-            bootstrap_class: status.to_bootstrap_class(),
-            alt_text: status.to_alt_text(),
-        }
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let Some(command) = args.next() else {
+        return Ok(Command::Upload);
+    };
+    if !matches!(command.as_str(), "check" | "table") {
+        return Err(format!("unknown command {}", command));
     }
-
-    fn from_result(request_result: ProtocolResult, protocol: HttpProtocol) -> Self {
-        match request_result {
-            ProtocolResult::Ok(status_code) => Self::new(
-                RequestResultResponseTemplate::Ok { status_code },
-                match protocol {
-                    HttpProtocol::Http => RequestResultStatus::NearlyCorrect,
-                    HttpProtocol::Https => RequestResultStatus::Correct,
-                },
-            ),
-            ProtocolResult::NginxDefaultPage(status_code) => Self::new(
-                RequestResultResponseTemplate::Ok { status_code },
-                RequestResultStatus::NearlyCorrect,
-            ),
-            ProtocolResult::CorrectRedirect(status_code) => Self::new(
-                RequestResultResponseTemplate::Redirect { status_code },
-                RequestResultStatus::Correct,
-            ),
-            ProtocolResult::IncorrectRedirect(status_code) => Self::new(
-                RequestResultResponseTemplate::Redirect { status_code },
-                RequestResultStatus::NearlyCorrect,
-            ),
-            ProtocolResult::UnexpectedResponse(status_code) => match status_code {
-                418 => Self::new(
-                    RequestResultResponseTemplate::Teapot,
-                    RequestResultStatus::NearlyCorrect,
-                ),
-                _ => Self::new(
-                    RequestResultResponseTemplate::UnexpectedResponse { status_code },
-                    RequestResultStatus::Incorrect,
-                ),
-            },
-            ProtocolResult::Timeout => Self::new(
-                RequestResultResponseTemplate::Timeout,
-                RequestResultStatus::Incorrect,
-            ),
-            ProtocolResult::UntrustedCertificate => Self::new(
-                RequestResultResponseTemplate::UntrustedCertificate,
-                RequestResultStatus::NearlyCorrect,
-            ),
-            ProtocolResult::InvalidCertificate => Self::new(
-                RequestResultResponseTemplate::InvalidCertificate,
-                RequestResultStatus::NearlyCorrect,
-            ),
-            ProtocolResult::FailedConnect => Self::new(
-                RequestResultResponseTemplate::FailedConnect,
-                RequestResultStatus::Incorrect,
-            ),
-            ProtocolResult::Error(_) => Self::new(
-                RequestResultResponseTemplate::Error,
-                RequestResultStatus::Incorrect,
-            ),
-        }
+    let mut format = Format::Table;
+    while let Some(arg) = args.next() {
+        let value = match arg.strip_prefix("--format=") {
+            Some(value) => value.to_owned(),
+            None if arg == "--format" => args.next().ok_or("--format needs a value")?,
+            None => return Err(format!("unknown argument {}", arg)),
+        };
+        format = value.parse()?;
     }
+    Ok(Command::Check { format })
 }
 
-#[derive(Clone, Serialize)]
-struct TeamResultTemplate {
-    team: Team,
-    http: RequestResultTemplate,
-    https: RequestResultTemplate,
-}
-
-impl From<TeamResult> for TeamResultTemplate {
-    fn from(team_result: TeamResult) -> Self {
-        Self {
-            team: team_result.team,
-            http: RequestResultTemplate::from_result(team_result.http, HttpProtocol::Http),
-            https: RequestResultTemplate::from_result(team_result.https, HttpProtocol::Https),
-        }
-    }
+/// See <https://no-color.org/>
+fn use_colour() -> bool {
+    io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
 }
 
 #[derive(Clone)]
 struct TestResultsCache {
     results: Vec<TeamResultTemplate>,
     timestamp: chrono::DateTime<chrono::Utc>,
+    /// The policy phase the results were marked under
+    phase: Option<String>,
 }
 
 struct AppState<'reg> {
     cache: RwLock<Option<TestResultsCache>>,
-    handlebars: handlebars::Handlebars<'reg>,
+    weights: Weights,
+    policy: Policy,
+    checker: Checker,
+    renderer: Renderer<'reg>,
     async_update_channel: tokio::sync::mpsc::Sender<()>,
 }
 
@@ -229,13 +135,19 @@ impl AppState<'_> {
             }
         }
 
-        let mut results: Vec<TeamResultTemplate> =
-            get_results().await.into_iter().map(|r| r.into()).collect();
-        results.sort_unstable_by_key(|r| r.team.team_number);
+        let rules = self.policy.rules_on(chrono::Utc::now().date_naive());
+        let mut results: Vec<TeamResultTemplate> = get_results(&self.checker)
+            .await
+            .unwrap_or_else(|e| panic!("failed to check teams: {}", e))
+            .into_iter()
+            .map(|r| TeamResultTemplate::new(r, &self.weights, &rules))
+            .collect();
+        results.sort_unstable_by_key(TeamResultTemplate::team_number);
 
         *cache = Some(TestResultsCache {
             results,
             timestamp: chrono::Utc::now(),
+            phase: rules.phase,
         });
     }
 
@@ -268,14 +180,10 @@ impl AppState<'_> {
 
 // #[get("/")]
 // async fn index(data: web::Data<AppState<'_>>) -> impl Responder {
-//     let (TestResultsCache { results, timestamp }, cache_state) = data.get_results().await;
-//     let timestamp_template = timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string();
-//     let html = data
-//         .handlebars
-//         .render(
-//             "main",
-//             &json!({ "teams": results, "timestamp": timestamp_template }),
-//         )
+//     let (TestResultsCache { results, timestamp, .. }, cache_state) = data.get_results().await;
+//     let mut html = vec![];
+//     data.renderer
+//         .index(&results, &History::load(timestamp), timestamp, None, &mut html)
 //         .unwrap();
 
 //     let mut response_builder = HttpResponse::Ok();
@@ -301,30 +209,91 @@ impl AppState<'_> {
 //         .body(include_bytes!("upload/favicon.ico").to_vec())
 // }
 
-handlebars_helper!(zero_pad: |x: i32| format!("{:02}", x));
-
 #[actix_web::main]
 async fn main() {
-    let mut handlebars = handlebars::Handlebars::new();
-    handlebars.register_helper("zero_pad", Box::new(zero_pad));
-    handlebars
-        .register_template_string("main", include_str!("index.html.hbs"))
-        .unwrap();
+    let command = parse_args(env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}\n\n{}", e, USAGE);
+        process::exit(2);
+    });
+    let mut checker = Checker::builder().journeys_dir(JOURNEYS_DIR);
+    if let Some(dir) = env::var_os("REPLAY_FIXTURES") {
+        checker = checker.replay_fixtures(dir);
+    }
+    if let Some(dir) = env::var_os("RECORD_FIXTURES") {
+        checker = checker.record_fixtures(dir);
+    }
+    #[allow(unused_mut, unused_variables)]
     let (async_update_channel, mut async_update_receiver) = tokio::sync::mpsc::channel(1);
     let app_data = web::Data::new(AppState {
         cache: RwLock::new(None),
-        handlebars,
+        weights: Weights::load(Path::new(SCORING_PATH)).unwrap_or_else(|e| panic!("{}", e)),
+        policy: Policy::load(Path::new(POLICY_PATH)).unwrap_or_else(|e| panic!("{}", e)),
+        checker: checker.build().unwrap(),
+        renderer: Renderer::default(),
         async_update_channel,
     });
 
-    let (TestResultsCache { results, timestamp }, _) = app_data.get_results().await;
-    let timestamp_template = timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string();
+    let (
+        TestResultsCache {
+            results,
+            timestamp,
+            phase,
+        },
+        _,
+    ) = app_data.get_results().await;
+    if let Command::Check { format } = command {
+        write_results(
+            &results,
+            timestamp,
+            phase.as_deref(),
+            format,
+            format == Format::Table && use_colour(),
+            io::stdout().lock(),
+        )
+        .unwrap();
+        return;
+    }
+    let mut history = History::load(timestamp);
+    for result in results.iter().filter(|result| result.is_deployed()) {
+        history.record_correct(result.team_number(), timestamp);
+    }
+    history.save();
+    app_data
+        .renderer
+        .index(
+            &results,
+            &history,
+            timestamp,
+            phase.as_deref(),
+            fs::File::create("upload/index.html").unwrap(),
+        )
+        .unwrap();
+    for team in &results {
+        app_data
+            .renderer
+            .team(
+                team,
+                timestamp,
+                fs::File::create(format!("upload/team-{:02}.html", team.team_number())).unwrap(),
+            )
+            .unwrap();
+    }
+    app_data
+        .renderer
+        .cohort(
+            &results,
+            timestamp,
+            fs::File::create("upload/cohort.html").unwrap(),
+        )
+        .unwrap();
+    // Not uploaded, as it points at leaked credentials
+    fs::create_dir_all("staff").unwrap();
     app_data
-        .handlebars
-        .render_to_write(
-            "main",
-            &json!({ "teams": results, "timestamp": timestamp_template }),
-            &mut fs::File::create("upload/index.html").unwrap(),
+        .renderer
+        .staff(
+            &results,
+            timestamp,
+            fs::File::create("staff/index.html").unwrap(),
         )
         .unwrap();
 
@@ -0,0 +1,240 @@
+//! How each HTTP and HTTPS result is marked, loaded from a YAML file such as the binary's
+//! `config/policy.yaml`, e.g.
+//!
+//! ```yaml
+//! rules:
+//!   served_instead_of_redirecting: incorrect
+//! phases:
+//!   - name: Final submission
+//!     from: 2023-03-20
+//!     rules:
+//!       untrusted_certificate: incorrect
+//!       invalid_certificate: incorrect
+//! ```
+//!
+//! Each result is marked `correct`, `nearly_correct` or `incorrect`. Rules that are left out use
+//! their default, and the latest phase that has started overrides the top-level rules, so the
+//! requirements can be tightened as the term goes on.
+
+use crate::config::{load_yaml, ConfigError};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
+
+/// How a result is marked, ordered from best to worst
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestResultStatus {
+    Correct,
+    NearlyCorrect,
+    Incorrect,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rule {
+    /// An endpoint serves content over HTTPS, as expected
+    ServedOverHttps,
+    /// Content is served over HTTP, or by an endpoint that has moved, rather than redirecting
+    ServedInsteadOfRedirecting,
+    NginxDefaultPage,
+    MixedContent,
+    CorrectRedirect,
+    IncorrectRedirect,
+    /// 418 I'm a teapot
+    Teapot,
+    UnexpectedResponse,
+    Timeout,
+    UntrustedCertificate,
+    InvalidCertificate,
+    FailedConnect,
+    Error,
+}
+
+impl Rule {
+    const fn default_status(self) -> RequestResultStatus {
+        match self {
+            Self::ServedOverHttps | Self::CorrectRedirect => RequestResultStatus::Correct,
+            Self::ServedInsteadOfRedirecting
+            | Self::NginxDefaultPage
+            | Self::MixedContent
+            | Self::IncorrectRedirect
+            | Self::Teapot
+            | Self::UntrustedCertificate
+            | Self::InvalidCertificate => RequestResultStatus::NearlyCorrect,
+            Self::UnexpectedResponse | Self::Timeout | Self::FailedConnect | Self::Error => {
+                RequestResultStatus::Incorrect
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Phase {
+    name: String,
+    /// The first day the phase's rules apply
+    from: NaiveDate,
+    #[serde(default)]
+    rules: BTreeMap<Rule, RequestResultStatus>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Policy {
+    rules: BTreeMap<Rule, RequestResultStatus>,
+    phases: Vec<Phase>,
+}
+
+impl Policy {
+    /// Uses the default rules if there's no file at `path`
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        load_yaml(path)
+    }
+
+    /// The rules in effect on `date`
+    #[must_use]
+    pub fn rules_on(&self, date: NaiveDate) -> Rules {
+        let phase = self
+            .phases
+            .iter()
+            .filter(|phase| phase.from <= date)
+            .max_by_key(|phase| phase.from);
+        let mut statuses = self.rules.clone();
+        if let Some(phase) = phase {
+            statuses.extend(&phase.rules);
+        }
+        Rules {
+            phase: phase.map(|phase| phase.name.clone()),
+            statuses,
+        }
+    }
+}
+
+pub struct Rules {
+    /// The name of the phase in effect, if any
+    pub phase: Option<String>,
+    statuses: BTreeMap<Rule, RequestResultStatus>,
+}
+
+impl Rules {
+    #[must_use]
+    pub fn status(&self, rule: Rule) -> RequestResultStatus {
+        self.statuses
+            .get(&rule)
+            .copied()
+            .unwrap_or_else(|| rule.default_status())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+
+    fn policy(yaml: &str) -> Policy {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn missing_policy_file_uses_default_rules() {
+        let rules = Policy::load(Path::new("config/missing.yaml"))
+            .unwrap()
+            .rules_on(date("2023-01-01"));
+        assert_eq!(rules.phase, None);
+        for rule in [
+            Rule::ServedOverHttps,
+            Rule::ServedInsteadOfRedirecting,
+            Rule::UntrustedCertificate,
+            Rule::Timeout,
+        ] {
+            assert_eq!(rules.status(rule), rule.default_status());
+        }
+    }
+
+    #[test]
+    fn invalid_policy_file_is_an_error() {
+        let path = std::env::temp_dir().join("invalid-policy.yaml");
+        std::fs::write(&path, "rules:\n  not_a_rule: correct\n").unwrap();
+        assert!(matches!(
+            Policy::load(&path),
+            Err(ConfigError::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn top_level_rules_override_defaults() {
+        let rules = policy("rules:\n  served_instead_of_redirecting: incorrect\n")
+            .rules_on(date("2023-01-01"));
+        assert_eq!(
+            rules.status(Rule::ServedInsteadOfRedirecting),
+            RequestResultStatus::Incorrect
+        );
+        assert_eq!(
+            rules.status(Rule::NginxDefaultPage),
+            RequestResultStatus::NearlyCorrect
+        );
+    }
+
+    #[test]
+    fn phases_apply_from_their_start() {
+        let policy = policy(
+            "phases:
+  - name: Final submission
+    from: 2023-03-20
+    rules:
+      untrusted_certificate: incorrect
+",
+        );
+
+        let before = policy.rules_on(date("2023-03-19"));
+        assert_eq!(before.phase, None);
+        assert_eq!(
+            before.status(Rule::UntrustedCertificate),
+            RequestResultStatus::NearlyCorrect
+        );
+
+        let from = policy.rules_on(date("2023-03-20"));
+        assert_eq!(from.phase.as_deref(), Some("Final submission"));
+        assert_eq!(
+            from.status(Rule::UntrustedCertificate),
+            RequestResultStatus::Incorrect
+        );
+    }
+
+    #[test]
+    fn only_the_latest_started_phase_applies() {
+        // Listed out of order, to check phases are ordered by when they start
+        let policy = policy(
+            "rules:
+  teapot: correct
+phases:
+  - name: Final submission
+    from: 2023-03-20
+    rules:
+      invalid_certificate: incorrect
+  - name: Prototype
+    from: 2023-02-01
+    rules:
+      untrusted_certificate: incorrect
+      teapot: incorrect
+",
+        );
+        let rules = policy.rules_on(date("2023-04-01"));
+        assert_eq!(rules.phase.as_deref(), Some("Final submission"));
+        assert_eq!(
+            rules.status(Rule::InvalidCertificate),
+            RequestResultStatus::Incorrect
+        );
+        // Set by the earlier phase only
+        assert_eq!(
+            rules.status(Rule::UntrustedCertificate),
+            RequestResultStatus::NearlyCorrect
+        );
+        // Set at the top level, and overridden by the earlier phase only
+        assert_eq!(rules.status(Rule::Teapot), RequestResultStatus::Correct);
+    }
+}
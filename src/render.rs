@@ -0,0 +1,380 @@
+//! Turns results into the status pages, with [`Renderer`], or into text with [`write_results`]
+
+mod assets;
+mod capabilities;
+mod cohort;
+mod crawl;
+mod exposure;
+mod fingerprint;
+mod health;
+mod http;
+mod hygiene;
+mod journey;
+mod ports;
+mod score;
+mod security;
+mod table;
+mod websocket;
+mod www;
+
+use crate::history::History;
+use crate::policy::{RequestResultStatus, Rules};
+use crate::scoring::Weights;
+use crate::status::{StackResult, TeamResult};
+use crate::teams::Team;
+use handlebars::{handlebars_helper, Handlebars, RenderError};
+use serde::Serialize;
+use serde_json::json;
+use std::io;
+
+use self::assets::AssetsResultResponseTemplate;
+use self::capabilities::CapabilitiesResultResponseTemplate;
+use self::cohort::cohort_stats;
+use self::crawl::CrawlResultResponseTemplate;
+use self::exposure::ExposureResultResponseTemplate;
+use self::fingerprint::{stack_summaries, TechnologyTemplate};
+use self::health::HealthCheckResultTemplate;
+use self::http::{EndpointResultTemplate, ProtocolResultTemplate};
+use self::hygiene::HygieneResultResponseTemplate;
+use self::journey::JourneyResultTemplate;
+use self::ports::PortsResultResponseTemplate;
+use self::score::ScoreTemplate;
+use self::security::SecurityResultResponseTemplate;
+pub use self::table::{write_results, Format};
+use self::websocket::WebSocketCheckResultTemplate;
+use self::www::WwwResultResponseTemplate;
+
+impl RequestResultStatus {
+    fn to_bootstrap_class(self) -> String {
+        match self {
+            Self::Correct => "link-success",
+            Self::NearlyCorrect => "link-warning",
+            Self::Incorrect => "link-danger",
+        }
+        .to_owned()
+    }
+
+    fn to_alt_text(self) -> String {
+        match self {
+            Self::Correct => "Correct",
+            Self::NearlyCorrect => "Nearly correct",
+            Self::Incorrect => "Incorrect",
+        }
+        .to_owned()
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct RequestResultTemplate<R> {
+    result: R,
+    bootstrap_class: String,
+    alt_text: String,
+    #[serde(skip)]
+    status: RequestResultStatus,
+}
+
+impl<R> RequestResultTemplate<R> {
+    fn new(result: R, status: RequestResultStatus) -> Self {
+        Self {
+            result,
+            status,
+            bootstrap_class: status.to_bootstrap_class(),
+            alt_text: status.to_alt_text(),
+        }
+    }
+}
+
+/// A team's results, marked and scored ready to render
+#[derive(Clone, Serialize)]
+pub struct TeamResultTemplate {
+    team: Team,
+    alternate_domain: String,
+    multiple_endpoints: bool,
+    /// The worst HTTP result across all endpoints
+    http: ProtocolResultTemplate,
+    /// The worst HTTPS result across all endpoints
+    https: ProtocolResultTemplate,
+    endpoints: Vec<EndpointResultTemplate>,
+    /// The worst API health check result, if the team has any
+    health: Option<HealthCheckResultTemplate>,
+    health_checks: Vec<HealthCheckResultTemplate>,
+    /// The worst journey result, if the team has any
+    journey: Option<JourneyResultTemplate>,
+    journeys: Vec<JourneyResultTemplate>,
+    /// The worst WebSocket result, if the team has any
+    websocket: Option<WebSocketCheckResultTemplate>,
+    websockets: Vec<WebSocketCheckResultTemplate>,
+    www: RequestResultTemplate<WwwResultResponseTemplate>,
+    capabilities: RequestResultTemplate<CapabilitiesResultResponseTemplate>,
+    hygiene: RequestResultTemplate<HygieneResultResponseTemplate>,
+    assets: RequestResultTemplate<AssetsResultResponseTemplate>,
+    crawl: RequestResultTemplate<CrawlResultResponseTemplate>,
+    security: RequestResultTemplate<SecurityResultResponseTemplate>,
+    /// Only rendered on the staff page
+    exposure: RequestResultTemplate<ExposureResultResponseTemplate>,
+    /// `None` if the homepage couldn't be fingerprinted
+    stack: Option<Vec<TechnologyTemplate>>,
+    /// Only rendered on the staff page, and `None` if the team hasn't opted in
+    ports: Option<RequestResultTemplate<PortsResultResponseTemplate>>,
+    score: ScoreTemplate,
+}
+
+impl TeamResultTemplate {
+    #[must_use]
+    pub const fn team_number(&self) -> u8 {
+        self.team.team_number
+    }
+
+    /// Whether HTTP and HTTPS are both correct
+    #[must_use]
+    pub fn is_deployed(&self) -> bool {
+        self.http.request.status == RequestResultStatus::Correct
+            && self.https.request.status == RequestResultStatus::Correct
+    }
+}
+
+impl TeamResultTemplate {
+    #[must_use]
+    pub fn new(team_result: TeamResult, weights: &Weights, rules: &Rules) -> Self {
+        let endpoints: Vec<EndpointResultTemplate> = team_result
+            .endpoints
+            .into_iter()
+            .map(|r| EndpointResultTemplate::new(r, rules))
+            .collect();
+        let worst = |protocol_result: fn(&EndpointResultTemplate) -> &ProtocolResultTemplate| {
+            endpoints
+                .iter()
+                .map(protocol_result)
+                .max_by_key(|r| r.request.status)
+                .unwrap()
+                .clone()
+        };
+        let health_checks: Vec<HealthCheckResultTemplate> = team_result
+            .health_checks
+            .into_iter()
+            .map(|r| r.into())
+            .collect();
+        let journeys: Vec<JourneyResultTemplate> =
+            team_result.journeys.into_iter().map(|r| r.into()).collect();
+        let websockets: Vec<WebSocketCheckResultTemplate> = team_result
+            .websockets
+            .into_iter()
+            .map(|r| r.into())
+            .collect();
+        let http = worst(|r| &r.http);
+        let https = worst(|r| &r.https);
+        let health = health_checks
+            .iter()
+            .max_by_key(|r| r.request.status)
+            .cloned();
+        let journey = journeys.iter().max_by_key(|r| r.request.status).cloned();
+        let websocket = websockets.iter().max_by_key(|r| r.request.status).cloned();
+        let www: RequestResultTemplate<WwwResultResponseTemplate> = team_result.www.into();
+        let capabilities: RequestResultTemplate<CapabilitiesResultResponseTemplate> =
+            team_result.capabilities.into();
+        let hygiene: RequestResultTemplate<HygieneResultResponseTemplate> =
+            team_result.hygiene.into();
+        let assets: RequestResultTemplate<AssetsResultResponseTemplate> = team_result.assets.into();
+        let crawl: RequestResultTemplate<CrawlResultResponseTemplate> = team_result.crawl.into();
+        let security: RequestResultTemplate<SecurityResultResponseTemplate> =
+            team_result.security.into();
+        let score = ScoreTemplate::new(&[
+            ("HTTP", weights.http, Some(http.request.status)),
+            ("HTTPS", weights.https, Some(https.request.status)),
+            ("www", weights.www, Some(www.status)),
+            (
+                "API health",
+                weights.health,
+                health.as_ref().map(|r| r.request.status),
+            ),
+            (
+                "Journey",
+                weights.journey,
+                journey.as_ref().map(|r| r.request.status),
+            ),
+            (
+                "WebSocket",
+                weights.websocket,
+                websocket.as_ref().map(|r| r.request.status),
+            ),
+            ("Protocols", weights.protocols, Some(capabilities.status)),
+            ("Performance hygiene", weights.hygiene, Some(hygiene.status)),
+            ("Page weight", weights.page_weight, Some(assets.status)),
+            ("Links", weights.links, Some(crawl.status)),
+            ("Security", weights.security, Some(security.status)),
+        ]);
+        Self {
+            alternate_domain: team_result.team.primary_endpoint().alternate_domain(),
+            team: team_result.team,
+            multiple_endpoints: endpoints.len() > 1,
+            http,
+            https,
+            endpoints,
+            health,
+            health_checks,
+            journey,
+            journeys,
+            websocket,
+            websockets,
+            www,
+            capabilities,
+            hygiene,
+            assets,
+            crawl,
+            security,
+            exposure: team_result.exposure.into(),
+            stack: match team_result.stack {
+                StackResult::Fingerprinted { technologies } => Some(
+                    technologies
+                        .into_iter()
+                        .map(|technology| TechnologyTemplate {
+                            name: technology.name,
+                            category: technology.category.as_str(),
+                        })
+                        .collect(),
+                ),
+                StackResult::Unavailable => None,
+            },
+            ports: team_result.ports.map(|ports| ports.into()),
+            score,
+        }
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes < 1_000_000 {
+        format!("{:.1} kB", bytes as f64 / 1000.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / 1_000_000.0)
+    }
+}
+
+/// Rounded to the nearest whole percent, or 0 if `whole` is 0
+fn percentage(part: usize, whole: usize) -> usize {
+    if whole == 0 {
+        0
+    } else {
+        (part as f64 * 100.0 / whole as f64).round() as usize
+    }
+}
+
+handlebars_helper!(zero_pad: |x: i32| format!("{:02}", x));
+handlebars_helper!(format_bytes: |x: u64| format_size(x));
+handlebars_helper!(format_points: |x: f64| format!("{}", x));
+handlebars_helper!(join: |items: Vec<String>, separator: str| items.join(separator));
+
+/// Renders the uploaded pages, and the staff page
+pub struct Renderer<'reg> {
+    handlebars: Handlebars<'reg>,
+}
+
+impl Default for Renderer<'_> {
+    fn default() -> Self {
+        let mut handlebars = Handlebars::new();
+        handlebars.register_helper("zero_pad", Box::new(zero_pad));
+        handlebars.register_helper("format_bytes", Box::new(format_bytes));
+        handlebars.register_helper("format_points", Box::new(format_points));
+        handlebars.register_helper("join", Box::new(join));
+        // Indenting partials would also indent the contents of `<pre>`
+        handlebars.set_prevent_indent(true);
+        handlebars
+            .register_partial("guidance", include_str!("guidance.html.hbs"))
+            .unwrap();
+        handlebars
+            .register_partial("diagnostic", include_str!("diagnostic.html.hbs"))
+            .unwrap();
+        handlebars
+            .register_partial("exchange", include_str!("exchange.html.hbs"))
+            .unwrap();
+        handlebars
+            .register_template_string("main", include_str!("index.html.hbs"))
+            .unwrap();
+        handlebars
+            .register_template_string("team", include_str!("team.html.hbs"))
+            .unwrap();
+        handlebars
+            .register_template_string("staff", include_str!("staff.html.hbs"))
+            .unwrap();
+        handlebars
+            .register_template_string("cohort", include_str!("cohort.html.hbs"))
+            .unwrap();
+        Self { handlebars }
+    }
+}
+
+fn format_timestamp(timestamp: chrono::DateTime<chrono::Utc>) -> String {
+    timestamp.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+}
+
+impl Renderer<'_> {
+    /// The index, with every team's results and the cohort's stats
+    pub fn index(
+        &self,
+        results: &[TeamResultTemplate],
+        history: &History,
+        timestamp: chrono::DateTime<chrono::Utc>,
+        phase: Option<&str>,
+        writer: impl io::Write,
+    ) -> Result<(), RenderError> {
+        self.handlebars.render_to_write(
+            "main",
+            &json!({
+                "teams": results,
+                "cohort": cohort_stats(results, history),
+                "timestamp": format_timestamp(timestamp),
+                "phase": phase,
+            }),
+            writer,
+        )
+    }
+
+    /// A team's detail page
+    pub fn team(
+        &self,
+        result: &TeamResultTemplate,
+        timestamp: chrono::DateTime<chrono::Utc>,
+        writer: impl io::Write,
+    ) -> Result<(), RenderError> {
+        self.handlebars.render_to_write(
+            "team",
+            &json!({ "team": result, "timestamp": format_timestamp(timestamp) }),
+            writer,
+        )
+    }
+
+    /// The technologies teams use, and how many of their teams are deployed
+    pub fn cohort(
+        &self,
+        results: &[TeamResultTemplate],
+        timestamp: chrono::DateTime<chrono::Utc>,
+        writer: impl io::Write,
+    ) -> Result<(), RenderError> {
+        let deployed = results.iter().filter(|result| result.is_deployed()).count();
+        self.handlebars.render_to_write(
+            "cohort",
+            &json!({
+                "stacks": stack_summaries(results),
+                "teams": results.len(),
+                "deployed": deployed,
+                "deployed_percentage": percentage(deployed, results.len()),
+                "unfingerprinted": results.iter().filter(|result| result.stack.is_none()).count(),
+                "timestamp": format_timestamp(timestamp),
+            }),
+            writer,
+        )
+    }
+
+    /// Exposed files and open ports, which mustn't be uploaded as they point at leaked credentials
+    pub fn staff(
+        &self,
+        results: &[TeamResultTemplate],
+        timestamp: chrono::DateTime<chrono::Utc>,
+        writer: impl io::Write,
+    ) -> Result<(), RenderError> {
+        self.handlebars.render_to_write(
+            "staff",
+            &json!({ "teams": results, "timestamp": format_timestamp(timestamp) }),
+            writer,
+        )
+    }
+}
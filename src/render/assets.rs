@@ -0,0 +1,95 @@
+use super::RequestResultTemplate;
+use crate::policy::RequestResultStatus;
+use crate::status::{Asset, AssetOutcome, AssetsResult};
+use serde::Serialize;
+
+/// Pages heavier than this are flagged, as they're usually unminified development builds
+const PAGE_WEIGHT_BUDGET_BYTES: usize = 2_000_000;
+/// The number of assets listed as the largest on the index
+const LARGEST_ASSETS: usize = 3;
+
+#[derive(Clone, Serialize)]
+pub(super) struct AssetTemplate {
+    url: String,
+    kind: &'static str,
+    bytes: Option<usize>,
+    status_code: Option<u16>,
+    broken: bool,
+}
+
+impl From<Asset> for AssetTemplate {
+    fn from(asset: Asset) -> Self {
+        let (bytes, status_code) = match asset.outcome {
+            AssetOutcome::Loaded { bytes } => (Some(bytes), None),
+            AssetOutcome::Failed { status_code } => (None, Some(status_code)),
+            AssetOutcome::Unreachable => (None, None),
+        };
+        Self {
+            url: asset.url,
+            kind: asset.kind.as_str(),
+            bytes,
+            status_code,
+            broken: bytes.is_none(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+pub(super) enum AssetsResultResponseTemplate {
+    #[serde(rename = "Inventoried")]
+    Inventoried {
+        total_bytes: usize,
+        requests: usize,
+        largest: Vec<AssetTemplate>,
+        broken: Vec<AssetTemplate>,
+        assets: Vec<AssetTemplate>,
+        truncated: bool,
+    },
+    #[serde(rename = "Unavailable")]
+    Unavailable,
+}
+
+impl From<AssetsResult> for RequestResultTemplate<AssetsResultResponseTemplate> {
+    fn from(assets_result: AssetsResult) -> Self {
+        match assets_result {
+            AssetsResult::Inventoried {
+                html_bytes,
+                assets,
+                truncated,
+            } => {
+                let assets: Vec<AssetTemplate> =
+                    assets.into_iter().map(|asset| asset.into()).collect();
+                let total_bytes = html_bytes + assets.iter().filter_map(|a| a.bytes).sum::<usize>();
+                let mut largest: Vec<AssetTemplate> =
+                    assets.iter().filter(|a| !a.broken).cloned().collect();
+                largest.sort_by_key(|a| std::cmp::Reverse(a.bytes));
+                largest.truncate(LARGEST_ASSETS);
+                let broken: Vec<AssetTemplate> =
+                    assets.iter().filter(|a| a.broken).cloned().collect();
+                let status = if !broken.is_empty() {
+                    RequestResultStatus::Incorrect
+                } else if total_bytes > PAGE_WEIGHT_BUDGET_BYTES {
+                    RequestResultStatus::NearlyCorrect
+                } else {
+                    RequestResultStatus::Correct
+                };
+                Self::new(
+                    AssetsResultResponseTemplate::Inventoried {
+                        total_bytes,
+                        requests: assets.len() + 1,
+                        largest,
+                        broken,
+                        assets,
+                        truncated,
+                    },
+                    status,
+                )
+            }
+            AssetsResult::Unavailable => Self::new(
+                AssetsResultResponseTemplate::Unavailable,
+                RequestResultStatus::Incorrect,
+            ),
+        }
+    }
+}
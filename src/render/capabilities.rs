@@ -0,0 +1,44 @@
+use super::RequestResultTemplate;
+use crate::policy::RequestResultStatus;
+use crate::status::CapabilitiesResult;
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+pub(super) enum CapabilitiesResultResponseTemplate {
+    #[serde(rename = "Negotiated")]
+    Negotiated {
+        alpn: &'static str,
+        http3: bool,
+        alt_svc: Option<String>,
+    },
+    #[serde(rename = "Unavailable")]
+    Unavailable,
+}
+
+impl From<CapabilitiesResult> for RequestResultTemplate<CapabilitiesResultResponseTemplate> {
+    fn from(capabilities_result: CapabilitiesResult) -> Self {
+        match capabilities_result {
+            CapabilitiesResult::Negotiated {
+                alpn,
+                http3,
+                alt_svc,
+            } => Self::new(
+                CapabilitiesResultResponseTemplate::Negotiated {
+                    alpn,
+                    http3,
+                    alt_svc,
+                },
+                if alpn == "h2" {
+                    RequestResultStatus::Correct
+                } else {
+                    RequestResultStatus::NearlyCorrect
+                },
+            ),
+            CapabilitiesResult::Unavailable => Self::new(
+                CapabilitiesResultResponseTemplate::Unavailable,
+                RequestResultStatus::Incorrect,
+            ),
+        }
+    }
+}
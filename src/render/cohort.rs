@@ -0,0 +1,106 @@
+use super::score::ScoreTemplate;
+use super::TeamResultTemplate;
+use crate::history::History;
+use crate::policy::RequestResultStatus;
+use serde::Serialize;
+
+/// The number of teams shown on the index's leaderboard
+const LEADERBOARD_SIZE: usize = 10;
+
+#[derive(Serialize)]
+pub(super) struct ProtocolStatsTemplate {
+    name: &'static str,
+    correct: usize,
+    nearly_correct: usize,
+    incorrect: usize,
+}
+
+impl ProtocolStatsTemplate {
+    fn new(name: &'static str, statuses: impl Iterator<Item = RequestResultStatus>) -> Self {
+        let mut stats = Self {
+            name,
+            correct: 0,
+            nearly_correct: 0,
+            incorrect: 0,
+        };
+        for status in statuses {
+            match status {
+                RequestResultStatus::Correct => stats.correct += 1,
+                RequestResultStatus::NearlyCorrect => stats.nearly_correct += 1,
+                RequestResultStatus::Incorrect => stats.incorrect += 1,
+            }
+        }
+        stats
+    }
+}
+
+#[derive(Serialize)]
+pub(super) struct LeaderboardEntryTemplate {
+    rank: usize,
+    team_number: u8,
+    score: ScoreTemplate,
+    /// Since the first run, e.g. `3d 4h`
+    time_to_first_correct: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(super) struct CohortStatsTemplate {
+    teams: usize,
+    /// Teams whose HTTP and HTTPS are both correct
+    fully_correct: usize,
+    protocols: Vec<ProtocolStatsTemplate>,
+    leaderboard: Vec<LeaderboardEntryTemplate>,
+}
+
+fn format_duration(duration: chrono::Duration) -> String {
+    if duration.num_days() > 0 {
+        format!("{}d {}h", duration.num_days(), duration.num_hours() % 24)
+    } else {
+        format!("{}h {}m", duration.num_hours(), duration.num_minutes() % 60)
+    }
+}
+
+/// Ranks teams by score, then by how quickly they were first correct
+pub(super) fn cohort_stats(
+    results: &[TeamResultTemplate],
+    history: &History,
+) -> CohortStatsTemplate {
+    let mut ranked: Vec<(&TeamResultTemplate, Option<chrono::Duration>)> = results
+        .iter()
+        .map(|result| {
+            (
+                result,
+                history.time_to_first_correct(result.team.team_number),
+            )
+        })
+        .collect();
+    ranked.sort_by_key(|(result, time_to_first_correct)| {
+        (
+            std::cmp::Reverse(result.score.score),
+            time_to_first_correct.is_none(),
+            *time_to_first_correct,
+            result.team.team_number,
+        )
+    });
+    CohortStatsTemplate {
+        teams: results.len(),
+        fully_correct: results.iter().filter(|result| result.is_deployed()).count(),
+        protocols: vec![
+            ProtocolStatsTemplate::new("HTTP", results.iter().map(|r| r.http.request.status)),
+            ProtocolStatsTemplate::new("HTTPS", results.iter().map(|r| r.https.request.status)),
+        ],
+        leaderboard: ranked
+            .into_iter()
+            .take(LEADERBOARD_SIZE)
+            .enumerate()
+            .map(
+                |(index, (result, time_to_first_correct))| LeaderboardEntryTemplate {
+                    rank: index + 1,
+                    team_number: result.team.team_number,
+                    score: result.score.clone(),
+                    time_to_first_correct: time_to_first_correct.map(format_duration),
+                },
+            )
+            .collect(),
+    }
+}
@@ -0,0 +1,93 @@
+use super::RequestResultTemplate;
+use crate::policy::RequestResultStatus;
+use crate::status::{BrokenLink, CrawlResult, LinkProblem};
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+pub(super) enum LinkProblemTemplate {
+    #[serde(rename = "Broken")]
+    Broken { status_code: u16 },
+    #[serde(rename = "Server error")]
+    ServerError { status_code: u16 },
+    #[serde(rename = "Redirect loop")]
+    RedirectLoop,
+    #[serde(rename = "Too many redirects")]
+    TooManyRedirects,
+    #[serde(rename = "Unreachable")]
+    Unreachable,
+}
+
+#[derive(Clone, Serialize)]
+pub(super) struct BrokenLinkTemplate {
+    url: String,
+    found_on: Option<String>,
+    problem: LinkProblemTemplate,
+}
+
+impl From<BrokenLink> for BrokenLinkTemplate {
+    fn from(broken_link: BrokenLink) -> Self {
+        Self {
+            url: broken_link.url,
+            found_on: broken_link.found_on,
+            problem: match broken_link.problem {
+                LinkProblem::Broken { status_code } => LinkProblemTemplate::Broken { status_code },
+                LinkProblem::ServerError { status_code } => {
+                    LinkProblemTemplate::ServerError { status_code }
+                }
+                LinkProblem::RedirectLoop => LinkProblemTemplate::RedirectLoop,
+                LinkProblem::TooManyRedirects => LinkProblemTemplate::TooManyRedirects,
+                LinkProblem::Unreachable => LinkProblemTemplate::Unreachable,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+pub(super) enum CrawlResultResponseTemplate {
+    #[serde(rename = "Crawled")]
+    Crawled {
+        pages: usize,
+        problems: Vec<BrokenLinkTemplate>,
+        truncated: bool,
+    },
+    #[serde(rename = "Unavailable")]
+    Unavailable,
+}
+
+impl From<CrawlResult> for RequestResultTemplate<CrawlResultResponseTemplate> {
+    fn from(crawl_result: CrawlResult) -> Self {
+        match crawl_result {
+            CrawlResult::Crawled {
+                pages,
+                problems,
+                truncated,
+            } => {
+                // Dead links are an annoyance, whereas failing pages are a broken site
+                let status = if problems
+                    .iter()
+                    .any(|problem| !matches!(problem.problem, LinkProblem::Broken { .. }))
+                {
+                    RequestResultStatus::Incorrect
+                } else if !problems.is_empty() {
+                    RequestResultStatus::NearlyCorrect
+                } else {
+                    RequestResultStatus::Correct
+                };
+                Self::new(
+                    CrawlResultResponseTemplate::Crawled {
+                        pages,
+                        problems: problems.into_iter().map(|problem| problem.into()).collect(),
+                        truncated,
+                    },
+                    status,
+                )
+            }
+            CrawlResult::Unavailable => Self::new(
+                CrawlResultResponseTemplate::Unavailable,
+                RequestResultStatus::Incorrect,
+            ),
+        }
+    }
+}
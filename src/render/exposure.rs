@@ -0,0 +1,52 @@
+use super::RequestResultTemplate;
+use crate::policy::RequestResultStatus;
+use crate::status::{ExposedPath, ExposureResult};
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
+pub(super) struct ExposedPathTemplate {
+    name: &'static str,
+    url: String,
+}
+
+impl From<ExposedPath> for ExposedPathTemplate {
+    fn from(exposed_path: ExposedPath) -> Self {
+        Self {
+            name: exposed_path.name,
+            url: exposed_path.url,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+pub(super) enum ExposureResultResponseTemplate {
+    #[serde(rename = "Probed")]
+    Probed { exposed: Vec<ExposedPathTemplate> },
+    #[serde(rename = "Unavailable")]
+    Unavailable,
+}
+
+impl From<ExposureResult> for RequestResultTemplate<ExposureResultResponseTemplate> {
+    fn from(exposure_result: ExposureResult) -> Self {
+        match exposure_result {
+            ExposureResult::Probed { exposed } => {
+                let status = if exposed.is_empty() {
+                    RequestResultStatus::Correct
+                } else {
+                    RequestResultStatus::Incorrect
+                };
+                Self::new(
+                    ExposureResultResponseTemplate::Probed {
+                        exposed: exposed.into_iter().map(|path| path.into()).collect(),
+                    },
+                    status,
+                )
+            }
+            ExposureResult::Unavailable => Self::new(
+                ExposureResultResponseTemplate::Unavailable,
+                RequestResultStatus::Incorrect,
+            ),
+        }
+    }
+}
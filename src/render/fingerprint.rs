@@ -0,0 +1,52 @@
+use super::{percentage, TeamResultTemplate};
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
+pub(super) struct TechnologyTemplate {
+    pub(super) name: &'static str,
+    pub(super) category: &'static str,
+}
+
+#[derive(Serialize)]
+pub(super) struct StackSummaryTemplate {
+    name: &'static str,
+    category: &'static str,
+    teams: Vec<u8>,
+    /// The number of those teams whose HTTP and HTTPS are both correct
+    deployed: usize,
+    deployed_percentage: usize,
+}
+
+/// Each detected technology with the teams using it, most common first within each category
+pub(super) fn stack_summaries(results: &[TeamResultTemplate]) -> Vec<StackSummaryTemplate> {
+    let mut summaries: Vec<StackSummaryTemplate> = vec![];
+    for result in results {
+        for technology in result.stack.iter().flatten() {
+            let index = match summaries
+                .iter()
+                .position(|summary| summary.name == technology.name)
+            {
+                Some(index) => index,
+                None => {
+                    summaries.push(StackSummaryTemplate {
+                        name: technology.name,
+                        category: technology.category,
+                        teams: vec![],
+                        deployed: 0,
+                        deployed_percentage: 0,
+                    });
+                    summaries.len() - 1
+                }
+            };
+            summaries[index].teams.push(result.team.team_number);
+            if result.is_deployed() {
+                summaries[index].deployed += 1;
+            }
+        }
+    }
+    for summary in &mut summaries {
+        summary.deployed_percentage = percentage(summary.deployed, summary.teams.len());
+    }
+    summaries.sort_by_key(|summary| (summary.category, std::cmp::Reverse(summary.teams.len())));
+    summaries
+}
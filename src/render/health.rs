@@ -0,0 +1,103 @@
+use super::RequestResultTemplate;
+use crate::policy::RequestResultStatus;
+use crate::status::{HealthCheckResult, HealthResult};
+use crate::teams::HealthCheck;
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+pub(super) enum HealthResultResponseTemplate {
+    #[serde(rename = "OK")]
+    Ok,
+    #[serde(rename = "Unexpected response")]
+    UnexpectedResponse { status_code: u16 },
+    #[serde(rename = "Not JSON")]
+    NotJson { content_type: Option<String> },
+    #[serde(rename = "Invalid JSON")]
+    InvalidJson,
+    #[serde(rename = "Assertion failed")]
+    AssertionFailed { assertion: String, actual: String },
+    #[serde(rename = "Invalid assertion")]
+    InvalidAssertion { assertion: String },
+    #[serde(rename = "Timeout")]
+    Timeout,
+    #[serde(rename = "Untrusted certificate")]
+    UntrustedCertificate,
+    #[serde(rename = "Invalid certificate")]
+    InvalidCertificate,
+    #[serde(rename = "Failed to connect")]
+    FailedConnect,
+    #[serde(rename = "Error")]
+    Error,
+}
+
+impl From<HealthResult> for RequestResultTemplate<HealthResultResponseTemplate> {
+    fn from(health_result: HealthResult) -> Self {
+        match health_result {
+            HealthResult::Ok => Self::new(
+                HealthResultResponseTemplate::Ok,
+                RequestResultStatus::Correct,
+            ),
+            HealthResult::UnexpectedResponse { status_code } => Self::new(
+                HealthResultResponseTemplate::UnexpectedResponse { status_code },
+                RequestResultStatus::Incorrect,
+            ),
+            HealthResult::NotJson { content_type } => Self::new(
+                HealthResultResponseTemplate::NotJson { content_type },
+                RequestResultStatus::Incorrect,
+            ),
+            HealthResult::InvalidJson => Self::new(
+                HealthResultResponseTemplate::InvalidJson,
+                RequestResultStatus::Incorrect,
+            ),
+            HealthResult::AssertionFailed { assertion, actual } => Self::new(
+                HealthResultResponseTemplate::AssertionFailed {
+                    assertion,
+                    actual: actual
+                        .map_or_else(|| "nothing".to_owned(), |actual| actual.to_string()),
+                },
+                RequestResultStatus::Incorrect,
+            ),
+            HealthResult::InvalidAssertion { assertion } => Self::new(
+                HealthResultResponseTemplate::InvalidAssertion { assertion },
+                RequestResultStatus::Incorrect,
+            ),
+            HealthResult::Timeout => Self::new(
+                HealthResultResponseTemplate::Timeout,
+                RequestResultStatus::Incorrect,
+            ),
+            HealthResult::UntrustedCertificate => Self::new(
+                HealthResultResponseTemplate::UntrustedCertificate,
+                RequestResultStatus::NearlyCorrect,
+            ),
+            HealthResult::InvalidCertificate => Self::new(
+                HealthResultResponseTemplate::InvalidCertificate,
+                RequestResultStatus::NearlyCorrect,
+            ),
+            HealthResult::FailedConnect => Self::new(
+                HealthResultResponseTemplate::FailedConnect,
+                RequestResultStatus::Incorrect,
+            ),
+            HealthResult::Error => Self::new(
+                HealthResultResponseTemplate::Error,
+                RequestResultStatus::Incorrect,
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub(super) struct HealthCheckResultTemplate {
+    pub(super) check: HealthCheck,
+    #[serde(flatten)]
+    pub(super) request: RequestResultTemplate<HealthResultResponseTemplate>,
+}
+
+impl From<HealthCheckResult> for HealthCheckResultTemplate {
+    fn from(health_check_result: HealthCheckResult) -> Self {
+        Self {
+            check: health_check_result.check,
+            request: health_check_result.result.into(),
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use super::RequestResultTemplate;
+use crate::guidance::{protocol_guidance, Guidance};
+use crate::policy::{Rule, Rules};
+use crate::status::{Diagnostic, EndpointResult, Exchange, HttpProtocol, ProtocolResult};
+use crate::teams::{Endpoint, Expected};
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+pub(super) enum RequestResultResponseTemplate {
+    #[serde(rename = "OK")]
+    Ok { status_code: u16 },
+    #[serde(rename = "Mixed content")]
+    MixedContent {
+        status_code: u16,
+        insecure_urls: Vec<String>,
+    },
+    #[serde(rename = "Teapot")]
+    Teapot,
+    #[serde(rename = "Redirect")]
+    Redirect { status_code: u16 },
+    #[serde(rename = "Unexpected response")]
+    UnexpectedResponse { status_code: u16 },
+    #[serde(rename = "Timeout")]
+    Timeout { diagnostic: Diagnostic },
+    #[serde(rename = "Untrusted certificate")]
+    UntrustedCertificate { diagnostic: Diagnostic },
+    #[serde(rename = "Invalid certificate")]
+    InvalidCertificate { diagnostic: Diagnostic },
+    #[serde(rename = "Failed to connect")]
+    FailedConnect { diagnostic: Diagnostic },
+    #[serde(rename = "Error")]
+    Error { diagnostic: Diagnostic },
+}
+
+impl RequestResultTemplate<RequestResultResponseTemplate> {
+    fn from_result(
+        request_result: ProtocolResult,
+        protocol: HttpProtocol,
+        expected: &Expected,
+        rules: &Rules,
+    ) -> Self {
+        let (result, rule) = match request_result {
+            ProtocolResult::Ok(status_code) => (
+                RequestResultResponseTemplate::Ok { status_code },
+                match (protocol, expected) {
+                    (HttpProtocol::Https, Expected::Serve) => Rule::ServedOverHttps,
+                    _ => Rule::ServedInsteadOfRedirecting,
+                },
+            ),
+            ProtocolResult::NginxDefaultPage(status_code) => (
+                RequestResultResponseTemplate::Ok { status_code },
+                Rule::NginxDefaultPage,
+            ),
+            ProtocolResult::MixedContent(status_code, insecure_urls) => (
+                RequestResultResponseTemplate::MixedContent {
+                    status_code,
+                    insecure_urls,
+                },
+                Rule::MixedContent,
+            ),
+            ProtocolResult::CorrectRedirect(status_code) => (
+                RequestResultResponseTemplate::Redirect { status_code },
+                Rule::CorrectRedirect,
+            ),
+            ProtocolResult::IncorrectRedirect(status_code) => (
+                RequestResultResponseTemplate::Redirect { status_code },
+                Rule::IncorrectRedirect,
+            ),
+            ProtocolResult::UnexpectedResponse(status_code) => match status_code {
+                418 => (RequestResultResponseTemplate::Teapot, Rule::Teapot),
+                _ => (
+                    RequestResultResponseTemplate::UnexpectedResponse { status_code },
+                    Rule::UnexpectedResponse,
+                ),
+            },
+            ProtocolResult::Timeout(diagnostic) => (
+                RequestResultResponseTemplate::Timeout { diagnostic },
+                Rule::Timeout,
+            ),
+            ProtocolResult::UntrustedCertificate(diagnostic) => (
+                RequestResultResponseTemplate::UntrustedCertificate { diagnostic },
+                Rule::UntrustedCertificate,
+            ),
+            ProtocolResult::InvalidCertificate(diagnostic) => (
+                RequestResultResponseTemplate::InvalidCertificate { diagnostic },
+                Rule::InvalidCertificate,
+            ),
+            ProtocolResult::FailedConnect(diagnostic) => (
+                RequestResultResponseTemplate::FailedConnect { diagnostic },
+                Rule::FailedConnect,
+            ),
+            ProtocolResult::Error(diagnostic) => (
+                RequestResultResponseTemplate::Error { diagnostic },
+                Rule::Error,
+            ),
+        };
+        Self::new(result, rules.status(rule))
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub(super) struct ProtocolResultTemplate {
+    pub(super) endpoint: String,
+    url: String,
+    #[serde(flatten)]
+    pub(super) request: RequestResultTemplate<RequestResultResponseTemplate>,
+    guidance: Option<Guidance>,
+    exchange: Exchange,
+}
+
+impl ProtocolResultTemplate {
+    fn new(
+        request_result: ProtocolResult,
+        exchange: Exchange,
+        protocol: HttpProtocol,
+        endpoint: &Endpoint,
+        rules: &Rules,
+    ) -> Self {
+        Self {
+            exchange,
+            guidance: protocol_guidance(&request_result, protocol, endpoint),
+            endpoint: endpoint.name.to_string(),
+            url: format!(
+                "{}://{}{}",
+                protocol.as_str(),
+                endpoint.domain,
+                endpoint.path
+            ),
+            request: RequestResultTemplate::from_result(
+                request_result,
+                protocol,
+                &endpoint.expected,
+                rules,
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub(super) struct EndpointResultTemplate {
+    endpoint: Endpoint,
+    pub(super) http: ProtocolResultTemplate,
+    pub(super) https: ProtocolResultTemplate,
+}
+
+impl EndpointResultTemplate {
+    pub(super) fn new(endpoint_result: EndpointResult, rules: &Rules) -> Self {
+        Self {
+            http: ProtocolResultTemplate::new(
+                endpoint_result.http,
+                endpoint_result.http_exchange,
+                HttpProtocol::Http,
+                &endpoint_result.endpoint,
+                rules,
+            ),
+            https: ProtocolResultTemplate::new(
+                endpoint_result.https,
+                endpoint_result.https_exchange,
+                HttpProtocol::Https,
+                &endpoint_result.endpoint,
+                rules,
+            ),
+            endpoint: endpoint_result.endpoint,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use super::RequestResultTemplate;
+use crate::policy::RequestResultStatus;
+use crate::status::{HygieneResult, ResourceHygiene};
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
+pub(super) struct ResourceHygieneTemplate {
+    url: String,
+    pub(super) content_encoding: Option<String>,
+    pub(super) transferred_bytes: usize,
+    pub(super) uncompressed_bytes: Option<usize>,
+    cache_control: Option<String>,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    compressed: bool,
+    cacheable: bool,
+    revalidatable: bool,
+}
+
+impl From<ResourceHygiene> for ResourceHygieneTemplate {
+    fn from(resource: ResourceHygiene) -> Self {
+        Self {
+            compressed: resource.is_compressed(),
+            cacheable: resource.is_cacheable(),
+            revalidatable: resource.is_revalidatable(),
+            url: resource.url,
+            content_encoding: resource.content_encoding,
+            transferred_bytes: resource.transferred_bytes,
+            uncompressed_bytes: resource.uncompressed_bytes,
+            cache_control: resource.cache_control,
+            etag: resource.etag,
+            last_modified: resource.last_modified,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+pub(super) enum HygieneResultResponseTemplate {
+    #[serde(rename = "Audited")]
+    Audited {
+        html: ResourceHygieneTemplate,
+        assets: Vec<ResourceHygieneTemplate>,
+    },
+    #[serde(rename = "Unavailable")]
+    Unavailable,
+}
+
+impl From<HygieneResult> for RequestResultTemplate<HygieneResultResponseTemplate> {
+    fn from(hygiene_result: HygieneResult) -> Self {
+        match hygiene_result {
+            HygieneResult::Audited { html, assets } => {
+                // HTML should at least be revalidatable, whereas static assets should be cached
+                let status = if html.is_compressed()
+                    && (html.is_cacheable() || html.is_revalidatable())
+                    && assets
+                        .iter()
+                        .all(|asset| asset.is_compressed() && asset.is_cacheable())
+                {
+                    RequestResultStatus::Correct
+                } else {
+                    RequestResultStatus::NearlyCorrect
+                };
+                Self::new(
+                    HygieneResultResponseTemplate::Audited {
+                        html: html.into(),
+                        assets: assets.into_iter().map(|asset| asset.into()).collect(),
+                    },
+                    status,
+                )
+            }
+            HygieneResult::Unavailable => Self::new(
+                HygieneResultResponseTemplate::Unavailable,
+                RequestResultStatus::Incorrect,
+            ),
+        }
+    }
+}
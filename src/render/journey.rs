@@ -0,0 +1,167 @@
+use super::RequestResultTemplate;
+use crate::policy::RequestResultStatus;
+use crate::status::{JourneyResult, StepOutcome, StepResult};
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+pub(super) enum StepOutcomeResponseTemplate {
+    #[serde(rename = "Passed")]
+    Passed { status_code: u16 },
+    #[serde(rename = "No steps")]
+    NoSteps,
+    #[serde(rename = "Unexpected response")]
+    UnexpectedResponse { status_code: u16 },
+    #[serde(rename = "Missing text")]
+    MissingText { text: String },
+    #[serde(rename = "Assertion failed")]
+    AssertionFailed { assertion: String, actual: String },
+    #[serde(rename = "Invalid assertion")]
+    InvalidAssertion { assertion: String },
+    #[serde(rename = "Capture failed")]
+    CaptureFailed { variable: String },
+    #[serde(rename = "Undefined variable")]
+    UndefinedVariable { variable: String },
+    #[serde(rename = "Invalid journey")]
+    InvalidJourney { error: String },
+    #[serde(rename = "Invalid request")]
+    InvalidRequest,
+    #[serde(rename = "Too many redirects")]
+    TooManyRedirects,
+    #[serde(rename = "Timeout")]
+    Timeout,
+    #[serde(rename = "Untrusted certificate")]
+    UntrustedCertificate,
+    #[serde(rename = "Invalid certificate")]
+    InvalidCertificate,
+    #[serde(rename = "Failed to connect")]
+    FailedConnect,
+    #[serde(rename = "Error")]
+    Error,
+}
+
+impl From<StepOutcome> for RequestResultTemplate<StepOutcomeResponseTemplate> {
+    fn from(step_outcome: StepOutcome) -> Self {
+        match step_outcome {
+            StepOutcome::Passed { status_code } => Self::new(
+                StepOutcomeResponseTemplate::Passed { status_code },
+                RequestResultStatus::Correct,
+            ),
+            StepOutcome::UnexpectedResponse { status_code } => Self::new(
+                StepOutcomeResponseTemplate::UnexpectedResponse { status_code },
+                RequestResultStatus::Incorrect,
+            ),
+            StepOutcome::MissingText { text } => Self::new(
+                StepOutcomeResponseTemplate::MissingText { text },
+                RequestResultStatus::Incorrect,
+            ),
+            StepOutcome::AssertionFailed { assertion, actual } => Self::new(
+                StepOutcomeResponseTemplate::AssertionFailed {
+                    assertion,
+                    actual: actual
+                        .map_or_else(|| "nothing".to_owned(), |actual| actual.to_string()),
+                },
+                RequestResultStatus::Incorrect,
+            ),
+            StepOutcome::InvalidAssertion { assertion } => Self::new(
+                StepOutcomeResponseTemplate::InvalidAssertion { assertion },
+                RequestResultStatus::Incorrect,
+            ),
+            StepOutcome::CaptureFailed { variable } => Self::new(
+                StepOutcomeResponseTemplate::CaptureFailed { variable },
+                RequestResultStatus::Incorrect,
+            ),
+            StepOutcome::UndefinedVariable { variable } => Self::new(
+                StepOutcomeResponseTemplate::UndefinedVariable { variable },
+                RequestResultStatus::Incorrect,
+            ),
+            StepOutcome::InvalidJourney { error } => Self::new(
+                StepOutcomeResponseTemplate::InvalidJourney { error },
+                RequestResultStatus::Incorrect,
+            ),
+            StepOutcome::InvalidRequest => Self::new(
+                StepOutcomeResponseTemplate::InvalidRequest,
+                RequestResultStatus::Incorrect,
+            ),
+            StepOutcome::TooManyRedirects => Self::new(
+                StepOutcomeResponseTemplate::TooManyRedirects,
+                RequestResultStatus::Incorrect,
+            ),
+            StepOutcome::Timeout => Self::new(
+                StepOutcomeResponseTemplate::Timeout,
+                RequestResultStatus::Incorrect,
+            ),
+            StepOutcome::UntrustedCertificate => Self::new(
+                StepOutcomeResponseTemplate::UntrustedCertificate,
+                RequestResultStatus::NearlyCorrect,
+            ),
+            StepOutcome::InvalidCertificate => Self::new(
+                StepOutcomeResponseTemplate::InvalidCertificate,
+                RequestResultStatus::NearlyCorrect,
+            ),
+            StepOutcome::FailedConnect => Self::new(
+                StepOutcomeResponseTemplate::FailedConnect,
+                RequestResultStatus::Incorrect,
+            ),
+            StepOutcome::Error => Self::new(
+                StepOutcomeResponseTemplate::Error,
+                RequestResultStatus::Incorrect,
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub(super) struct StepResultTemplate {
+    name: String,
+    method: String,
+    url: String,
+    #[serde(flatten)]
+    request: RequestResultTemplate<StepOutcomeResponseTemplate>,
+}
+
+impl From<StepResult> for StepResultTemplate {
+    fn from(step_result: StepResult) -> Self {
+        Self {
+            name: step_result.name,
+            method: step_result.method,
+            url: step_result.url,
+            request: step_result.outcome.into(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub(super) struct JourneyResultTemplate {
+    pub(super) name: String,
+    pub(super) failed_step: Option<String>,
+    #[serde(flatten)]
+    pub(super) request: RequestResultTemplate<StepOutcomeResponseTemplate>,
+    steps: Vec<StepResultTemplate>,
+}
+
+impl From<JourneyResult> for JourneyResultTemplate {
+    fn from(journey_result: JourneyResult) -> Self {
+        let steps: Vec<StepResultTemplate> =
+            journey_result.steps.into_iter().map(|r| r.into()).collect();
+        // Steps stop at the first failure, so the last step decides the journey's result
+        let request = steps.last().map_or_else(
+            || {
+                RequestResultTemplate::new(
+                    StepOutcomeResponseTemplate::NoSteps,
+                    RequestResultStatus::Incorrect,
+                )
+            },
+            |step| step.request.clone(),
+        );
+        Self {
+            name: journey_result.name,
+            failed_step: steps
+                .last()
+                .filter(|step| step.request.status != RequestResultStatus::Correct)
+                .map(|step| step.name.clone()),
+            request,
+            steps,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use super::RequestResultTemplate;
+use crate::policy::RequestResultStatus;
+use crate::status::{OpenPort, PortsResult};
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
+pub(super) struct OpenPortTemplate {
+    port: u16,
+    service: Option<&'static str>,
+    banner: Option<String>,
+    datastore: bool,
+}
+
+impl From<OpenPort> for OpenPortTemplate {
+    fn from(open_port: OpenPort) -> Self {
+        Self {
+            datastore: open_port.is_datastore(),
+            port: open_port.port,
+            service: open_port.service,
+            banner: open_port.banner,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+pub(super) enum PortsResultResponseTemplate {
+    #[serde(rename = "Audited")]
+    Audited {
+        address: String,
+        open: Vec<OpenPortTemplate>,
+    },
+    #[serde(rename = "Unresolved")]
+    Unresolved,
+}
+
+impl From<PortsResult> for RequestResultTemplate<PortsResultResponseTemplate> {
+    fn from(ports_result: PortsResult) -> Self {
+        match ports_result {
+            PortsResult::Audited { address, open } => {
+                // SSH is expected, as teams manage their servers over it
+                let status = if open.iter().any(OpenPort::is_datastore) {
+                    RequestResultStatus::Incorrect
+                } else if open.iter().any(|open_port| open_port.port != 22) {
+                    RequestResultStatus::NearlyCorrect
+                } else {
+                    RequestResultStatus::Correct
+                };
+                Self::new(
+                    PortsResultResponseTemplate::Audited {
+                        address,
+                        open: open.into_iter().map(|open_port| open_port.into()).collect(),
+                    },
+                    status,
+                )
+            }
+            PortsResult::Unresolved => Self::new(
+                PortsResultResponseTemplate::Unresolved,
+                RequestResultStatus::Incorrect,
+            ),
+        }
+    }
+}
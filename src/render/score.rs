@@ -0,0 +1,73 @@
+use crate::policy::RequestResultStatus;
+use crate::scoring;
+use serde::Serialize;
+
+impl RequestResultStatus {
+    /// The proportion of a check's weight a team is awarded
+    const fn credit(self) -> f64 {
+        match self {
+            Self::Correct => 1.0,
+            Self::NearlyCorrect => 0.5,
+            Self::Incorrect => 0.0,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub(super) struct ScoreComponentTemplate {
+    name: &'static str,
+    weight: f64,
+    points: f64,
+    bootstrap_class: String,
+    alt_text: String,
+}
+
+#[derive(Clone, Serialize)]
+pub(super) struct ScoreTemplate {
+    /// Out of 100
+    pub(super) score: u8,
+    pub(super) grade: &'static str,
+    bootstrap_class: String,
+    components: Vec<ScoreComponentTemplate>,
+    #[serde(skip)]
+    pub(super) status: RequestResultStatus,
+}
+
+impl ScoreTemplate {
+    /// Combines the name, weight and status of each check, skipping those that aren't configured
+    pub(super) fn new(components: &[(&'static str, f64, Option<RequestResultStatus>)]) -> Self {
+        let components: Vec<ScoreComponentTemplate> = components
+            .iter()
+            .filter(|(_, weight, _)| *weight > 0.0)
+            .filter_map(|(name, weight, status)| {
+                status.map(|status| ScoreComponentTemplate {
+                    name,
+                    weight: *weight,
+                    points: weight * status.credit(),
+                    bootstrap_class: status.to_bootstrap_class(),
+                    alt_text: status.to_alt_text(),
+                })
+            })
+            .collect();
+        let total_weight: f64 = components.iter().map(|component| component.weight).sum();
+        let points: f64 = components.iter().map(|component| component.points).sum();
+        let score = if total_weight > 0.0 {
+            (points * 100.0 / total_weight).round() as u8
+        } else {
+            0
+        };
+        let grade = scoring::grade(score);
+        let status = match grade {
+            "A" | "B" => RequestResultStatus::Correct,
+            "C" | "D" => RequestResultStatus::NearlyCorrect,
+            _ => RequestResultStatus::Incorrect,
+        };
+        Self {
+            score,
+            grade,
+            bootstrap_class: status.to_bootstrap_class(),
+            components,
+            status,
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use super::RequestResultTemplate;
+use crate::policy::RequestResultStatus;
+use crate::status::{SecurityFinding, SecurityResult, Severity};
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+pub(super) enum SecurityFindingResponseTemplate {
+    #[serde(rename = "Insecure cookie")]
+    InsecureCookie {
+        url: String,
+        name: String,
+        missing: Vec<&'static str>,
+    },
+    #[serde(rename = "Session cookie over HTTP")]
+    SessionCookieOverHttp { url: String, name: String },
+    #[serde(rename = "Version disclosed by")]
+    VersionDisclosure {
+        url: String,
+        header: &'static str,
+        value: String,
+    },
+    #[serde(rename = "Directory listing")]
+    DirectoryListing { url: String },
+}
+
+#[derive(Clone, Serialize)]
+pub(super) struct SecurityFindingTemplate {
+    severity: &'static str,
+    #[serde(flatten)]
+    request: RequestResultTemplate<SecurityFindingResponseTemplate>,
+}
+
+impl From<SecurityFinding> for SecurityFindingTemplate {
+    fn from(finding: SecurityFinding) -> Self {
+        let severity = finding.severity();
+        let result = match finding {
+            SecurityFinding::InsecureCookie {
+                url, name, missing, ..
+            } => SecurityFindingResponseTemplate::InsecureCookie { url, name, missing },
+            SecurityFinding::SessionCookieOverHttp { url, name } => {
+                SecurityFindingResponseTemplate::SessionCookieOverHttp { url, name }
+            }
+            SecurityFinding::VersionDisclosure { url, header, value } => {
+                SecurityFindingResponseTemplate::VersionDisclosure { url, header, value }
+            }
+            SecurityFinding::DirectoryListing { url } => {
+                SecurityFindingResponseTemplate::DirectoryListing { url }
+            }
+        };
+        Self {
+            severity: severity.as_str(),
+            request: RequestResultTemplate::new(
+                result,
+                match severity {
+                    Severity::High => RequestResultStatus::Incorrect,
+                    Severity::Medium | Severity::Low => RequestResultStatus::NearlyCorrect,
+                },
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+pub(super) enum SecurityResultResponseTemplate {
+    #[serde(rename = "Inspected")]
+    Inspected {
+        findings: Vec<SecurityFindingTemplate>,
+    },
+    #[serde(rename = "Unavailable")]
+    Unavailable,
+}
+
+impl From<SecurityResult> for RequestResultTemplate<SecurityResultResponseTemplate> {
+    fn from(security_result: SecurityResult) -> Self {
+        match security_result {
+            SecurityResult::Inspected { findings } => {
+                let mut findings: Vec<SecurityFindingTemplate> =
+                    findings.into_iter().map(|finding| finding.into()).collect();
+                findings.sort_by_key(|finding| std::cmp::Reverse(finding.request.status));
+                let status = findings
+                    .first()
+                    .map_or(RequestResultStatus::Correct, |finding| {
+                        finding.request.status
+                    });
+                Self::new(
+                    SecurityResultResponseTemplate::Inspected { findings },
+                    status,
+                )
+            }
+            SecurityResult::Unavailable => Self::new(
+                SecurityResultResponseTemplate::Unavailable,
+                RequestResultStatus::Incorrect,
+            ),
+        }
+    }
+}
@@ -0,0 +1,436 @@
+//! The index's table as text, for terminals and scripts. Like the index, it leaves out exposed
+//! files and open ports, so its output can be shared with teams.
+
+use super::assets::AssetsResultResponseTemplate;
+use super::capabilities::CapabilitiesResultResponseTemplate;
+use super::http::ProtocolResultTemplate;
+use super::hygiene::HygieneResultResponseTemplate;
+use super::security::SecurityResultResponseTemplate;
+use super::{format_size, RequestResultTemplate, TeamResultTemplate};
+use crate::policy::RequestResultStatus;
+use serde::Serialize;
+use std::{io, str::FromStr};
+
+const HEADERS: [&str; 12] = [
+    "Team",
+    "Score",
+    "HTTP",
+    "HTTPS",
+    "www",
+    "API health",
+    "Journey",
+    "WebSocket",
+    "Protocols",
+    "Performance hygiene",
+    "Page weight",
+    "Security",
+];
+
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// Aligned columns for terminals, colour-coded like the index
+    Table,
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            "markdown" => Ok(Self::Markdown),
+            _ => Err(format!(
+                "unknown format {}, expected table, json, csv or markdown",
+                format
+            )),
+        }
+    }
+}
+
+impl RequestResultStatus {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Correct => "correct",
+            Self::NearlyCorrect => "nearly_correct",
+            Self::Incorrect => "incorrect",
+        }
+    }
+
+    const fn ansi_colour(self) -> &'static str {
+        match self {
+            Self::Correct => "\x1b[32m",
+            Self::NearlyCorrect => "\x1b[33m",
+            Self::Incorrect => "\x1b[31m",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Cell {
+    text: String,
+    /// `None` if the check isn't configured
+    status: Option<RequestResultStatus>,
+}
+
+impl Cell {
+    const fn new<R>(request: &RequestResultTemplate<R>, text: String) -> Self {
+        Self {
+            text,
+            status: Some(request.status),
+        }
+    }
+
+    fn not_configured() -> Self {
+        Self {
+            text: "Not configured".to_owned(),
+            status: None,
+        }
+    }
+}
+
+/// A result's type, with its status code if it has one
+fn summary(result: &impl Serialize) -> String {
+    let result = serde_json::to_value(result).unwrap();
+    let kind = result["type"].as_str().unwrap_or_default();
+    match result["status_code"].as_u64() {
+        Some(status_code) => format!("{} ({})", kind, status_code),
+        None => kind.to_owned(),
+    }
+}
+
+#[derive(Serialize)]
+struct Row {
+    team: u8,
+    score: Cell,
+    http: Cell,
+    https: Cell,
+    www: Cell,
+    health: Cell,
+    journey: Cell,
+    websocket: Cell,
+    protocols: Cell,
+    hygiene: Cell,
+    page_weight: Cell,
+    security: Cell,
+}
+
+impl Row {
+    fn new(result: &TeamResultTemplate) -> Self {
+        let protocol = |protocol: &ProtocolResultTemplate| {
+            let text = summary(&protocol.request.result);
+            Cell::new(
+                &protocol.request,
+                if result.multiple_endpoints {
+                    format!("{}: {}", protocol.endpoint, text)
+                } else {
+                    text
+                },
+            )
+        };
+        Self {
+            team: result.team.team_number,
+            score: Cell {
+                text: format!("{} ({})", result.score.score, result.score.grade),
+                status: Some(result.score.status),
+            },
+            http: protocol(&result.http),
+            https: protocol(&result.https),
+            www: Cell::new(&result.www, {
+                let www = serde_json::to_value(&result.www.result).unwrap();
+                let kind = www["type"].as_str().unwrap_or_default();
+                match (www["canonical"].as_str(), www["host"].as_str()) {
+                    (Some(canonical), _) => format!("{} ({})", kind, canonical),
+                    (None, Some(host)) => match www["status_code"].as_u64() {
+                        Some(status_code) => format!("{} ({}, {})", kind, host, status_code),
+                        None => format!("{} ({})", kind, host),
+                    },
+                    (None, None) => kind.to_owned(),
+                }
+            }),
+            health: result
+                .health
+                .as_ref()
+                .map_or_else(Cell::not_configured, |health| {
+                    Cell::new(
+                        &health.request,
+                        format!("{}: {}", health.check.name, summary(&health.request.result)),
+                    )
+                }),
+            journey: result
+                .journey
+                .as_ref()
+                .map_or_else(Cell::not_configured, |journey| {
+                    let text = summary(&journey.request.result);
+                    Cell::new(
+                        &journey.request,
+                        match &journey.failed_step {
+                            Some(failed_step) => {
+                                format!("{}: {}: {}", journey.name, failed_step, text)
+                            }
+                            None => format!("{}: {}", journey.name, text),
+                        },
+                    )
+                }),
+            websocket: result
+                .websocket
+                .as_ref()
+                .map_or_else(Cell::not_configured, |websocket| {
+                    Cell::new(
+                        &websocket.request,
+                        format!(
+                            "{}: {}",
+                            websocket.check.name,
+                            summary(&websocket.request.result)
+                        ),
+                    )
+                }),
+            protocols: Cell::new(
+                &result.capabilities,
+                match &result.capabilities.result {
+                    CapabilitiesResultResponseTemplate::Negotiated { alpn, http3, .. }
+                        if !alpn.is_empty() =>
+                    {
+                        if *http3 {
+                            format!("{}, h3 advertised", alpn)
+                        } else {
+                            (*alpn).to_owned()
+                        }
+                    }
+                    capabilities => summary(capabilities),
+                },
+            ),
+            hygiene: Cell::new(
+                &result.hygiene,
+                match &result.hygiene.result {
+                    HygieneResultResponseTemplate::Audited { html, .. } => {
+                        match (&html.content_encoding, html.uncompressed_bytes) {
+                            (Some(content_encoding), Some(uncompressed_bytes)) => format!(
+                                "{} ({} of {})",
+                                content_encoding,
+                                format_size(html.transferred_bytes as u64),
+                                format_size(uncompressed_bytes as u64)
+                            ),
+                            (Some(content_encoding), None) => format!(
+                                "{} ({})",
+                                content_encoding,
+                                format_size(html.transferred_bytes as u64)
+                            ),
+                            (None, _) => format!(
+                                "Uncompressed ({})",
+                                format_size(html.transferred_bytes as u64)
+                            ),
+                        }
+                    }
+                    hygiene @ HygieneResultResponseTemplate::Unavailable => summary(hygiene),
+                },
+            ),
+            page_weight: Cell::new(
+                &result.assets,
+                match &result.assets.result {
+                    AssetsResultResponseTemplate::Inventoried {
+                        total_bytes,
+                        requests,
+                        broken,
+                        ..
+                    } if *requests > 0 => {
+                        let text = format!(
+                            "{}, {} requests",
+                            format_size(*total_bytes as u64),
+                            requests
+                        );
+                        if broken.is_empty() {
+                            text
+                        } else {
+                            format!("{} ({} broken)", text, broken.len())
+                        }
+                    }
+                    assets => summary(assets),
+                },
+            ),
+            security: Cell::new(
+                &result.security,
+                match &result.security.result {
+                    SecurityResultResponseTemplate::Inspected { findings } => {
+                        if findings.is_empty() {
+                            "No findings".to_owned()
+                        } else {
+                            format!("{} findings", findings.len())
+                        }
+                    }
+                    security @ SecurityResultResponseTemplate::Unavailable => summary(security),
+                },
+            ),
+        }
+    }
+
+    /// Every column but the team number, in the order of [`HEADERS`]
+    const fn cells(&self) -> [&Cell; 11] {
+        [
+            &self.score,
+            &self.http,
+            &self.https,
+            &self.www,
+            &self.health,
+            &self.journey,
+            &self.websocket,
+            &self.protocols,
+            &self.hygiene,
+            &self.page_weight,
+            &self.security,
+        ]
+    }
+}
+
+#[derive(Serialize)]
+struct Results<'a> {
+    timestamp: &'a str,
+    /// The policy phase the results were marked under
+    phase: Option<&'a str>,
+    teams: &'a [Row],
+}
+
+/// Writes the index's table in `format`. Colour only applies to [`Format::Table`]
+pub fn write_results(
+    results: &[TeamResultTemplate],
+    timestamp: chrono::DateTime<chrono::Utc>,
+    phase: Option<&str>,
+    format: Format,
+    colour: bool,
+    mut writer: impl io::Write,
+) -> io::Result<()> {
+    let rows: Vec<Row> = results.iter().map(Row::new).collect();
+    let timestamp = super::format_timestamp(timestamp);
+    match format {
+        Format::Table => {
+            write_table(&rows, colour, &mut writer)?;
+            writeln!(writer)?;
+            writeln!(writer, "As of {}", timestamp)?;
+            if let Some(phase) = phase {
+                writeln!(writer, "Marked against the {} requirements", phase)?;
+            }
+            Ok(())
+        }
+        Format::Json => {
+            serde_json::to_writer_pretty(
+                &mut writer,
+                &Results {
+                    timestamp: &timestamp,
+                    phase,
+                    teams: &rows,
+                },
+            )?;
+            writeln!(writer)
+        }
+        Format::Csv => write_csv(&rows, writer),
+        Format::Markdown => write_markdown(&rows, writer),
+    }
+}
+
+fn write_table(rows: &[Row], colour: bool, mut writer: impl io::Write) -> io::Result<()> {
+    let lines: Vec<Vec<(String, Option<RequestResultStatus>)>> = rows
+        .iter()
+        .map(|row| {
+            std::iter::once((row.team.to_string(), None))
+                .chain(row.cells().map(|cell| (cell.text.clone(), cell.status)))
+                .collect()
+        })
+        .collect();
+    let mut widths = HEADERS.map(|header| header.chars().count());
+    for line in &lines {
+        for (width, (text, _)) in widths.iter_mut().zip(line) {
+            *width = (*width).max(text.chars().count());
+        }
+    }
+    let write_line = |writer: &mut dyn io::Write, line: &[(&str, Option<&str>)]| {
+        for (column, (text, style)) in line.iter().enumerate() {
+            if column > 0 {
+                write!(writer, "  ")?;
+            }
+            match style {
+                Some(style) if colour => write!(writer, "{}{}{}", style, text, RESET)?,
+                _ => write!(writer, "{}", text)?,
+            }
+            // Padding the last column would only leave trailing whitespace
+            if column + 1 < line.len() {
+                let padding = widths[column] - text.chars().count();
+                write!(writer, "{:padding$}", "", padding = padding)?;
+            }
+        }
+        writeln!(writer)
+    };
+    let headers: Vec<_> = HEADERS.iter().map(|header| (*header, Some(BOLD))).collect();
+    write_line(&mut writer, &headers)?;
+    for line in &lines {
+        let line: Vec<_> = line
+            .iter()
+            .enumerate()
+            .map(|(column, (text, status))| {
+                let style = match status {
+                    Some(status) => status.ansi_colour(),
+                    // The team number
+                    None if column == 0 => BOLD,
+                    None => DIM,
+                };
+                (text.as_str(), Some(style))
+            })
+            .collect();
+        write_line(&mut writer, &line)?;
+    }
+    Ok(())
+}
+
+/// Quotes `field` if it contains anything CSV treats specially
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Each column is followed by its status, e.g. `nearly_correct`, which is empty if the check isn't
+/// configured
+fn write_csv(rows: &[Row], mut writer: impl io::Write) -> io::Result<()> {
+    let mut headers = vec![HEADERS[0].to_owned()];
+    for header in &HEADERS[1..] {
+        headers.push((*header).to_owned());
+        headers.push(format!("{} status", header));
+    }
+    writeln!(writer, "{}", headers.join(","))?;
+    for row in rows {
+        let mut fields = vec![row.team.to_string()];
+        for cell in row.cells() {
+            fields.push(csv_field(&cell.text));
+            fields.push(
+                cell.status
+                    .map_or("", RequestResultStatus::as_str)
+                    .to_owned(),
+            );
+        }
+        writeln!(writer, "{}", fields.join(","))?;
+    }
+    Ok(())
+}
+
+fn write_markdown(rows: &[Row], mut writer: impl io::Write) -> io::Result<()> {
+    writeln!(writer, "| {} |", HEADERS.join(" | "))?;
+    writeln!(writer, "|{}", " --- |".repeat(HEADERS.len()))?;
+    for row in rows {
+        let cells: Vec<String> = row
+            .cells()
+            .iter()
+            .map(|cell| cell.text.replace('|', r"\|"))
+            .collect();
+        writeln!(writer, "| {} | {} |", row.team, cells.join(" | "))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use super::RequestResultTemplate;
+use crate::policy::RequestResultStatus;
+use crate::status::{WebSocketCheckResult, WebSocketResult};
+use crate::teams::WebSocketCheck;
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+pub(super) enum WebSocketResultResponseTemplate {
+    #[serde(rename = "Connected")]
+    Connected,
+    #[serde(rename = "Replied")]
+    Replied,
+    #[serde(rename = "No reply")]
+    NoReply,
+    #[serde(rename = "Closed")]
+    Closed,
+    #[serde(rename = "Handshake rejected")]
+    HandshakeRejected { status_code: u16 },
+    #[serde(rename = "Timeout")]
+    Timeout,
+    #[serde(rename = "Untrusted certificate")]
+    UntrustedCertificate,
+    #[serde(rename = "Invalid certificate")]
+    InvalidCertificate,
+    #[serde(rename = "Failed to connect")]
+    FailedConnect,
+    #[serde(rename = "Error")]
+    Error,
+}
+
+impl From<WebSocketResult> for RequestResultTemplate<WebSocketResultResponseTemplate> {
+    fn from(websocket_result: WebSocketResult) -> Self {
+        match websocket_result {
+            WebSocketResult::Connected => Self::new(
+                WebSocketResultResponseTemplate::Connected,
+                RequestResultStatus::Correct,
+            ),
+            WebSocketResult::Replied => Self::new(
+                WebSocketResultResponseTemplate::Replied,
+                RequestResultStatus::Correct,
+            ),
+            WebSocketResult::NoReply => Self::new(
+                WebSocketResultResponseTemplate::NoReply,
+                RequestResultStatus::NearlyCorrect,
+            ),
+            WebSocketResult::Closed => Self::new(
+                WebSocketResultResponseTemplate::Closed,
+                RequestResultStatus::NearlyCorrect,
+            ),
+            WebSocketResult::HandshakeRejected { status_code } => Self::new(
+                WebSocketResultResponseTemplate::HandshakeRejected { status_code },
+                RequestResultStatus::Incorrect,
+            ),
+            WebSocketResult::Timeout => Self::new(
+                WebSocketResultResponseTemplate::Timeout,
+                RequestResultStatus::Incorrect,
+            ),
+            WebSocketResult::UntrustedCertificate => Self::new(
+                WebSocketResultResponseTemplate::UntrustedCertificate,
+                RequestResultStatus::NearlyCorrect,
+            ),
+            WebSocketResult::InvalidCertificate => Self::new(
+                WebSocketResultResponseTemplate::InvalidCertificate,
+                RequestResultStatus::NearlyCorrect,
+            ),
+            WebSocketResult::FailedConnect => Self::new(
+                WebSocketResultResponseTemplate::FailedConnect,
+                RequestResultStatus::Incorrect,
+            ),
+            WebSocketResult::Error => Self::new(
+                WebSocketResultResponseTemplate::Error,
+                RequestResultStatus::Incorrect,
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub(super) struct WebSocketCheckResultTemplate {
+    pub(super) check: WebSocketCheck,
+    #[serde(flatten)]
+    pub(super) request: RequestResultTemplate<WebSocketResultResponseTemplate>,
+}
+
+impl From<WebSocketCheckResult> for WebSocketCheckResultTemplate {
+    fn from(websocket_check_result: WebSocketCheckResult) -> Self {
+        Self {
+            check: websocket_check_result.check,
+            request: websocket_check_result.result.into(),
+        }
+    }
+}
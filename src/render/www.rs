@@ -0,0 +1,76 @@
+use super::RequestResultTemplate;
+use crate::policy::RequestResultStatus;
+use crate::status::WwwResult;
+use serde::Serialize;
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+pub(super) enum WwwResultResponseTemplate {
+    #[serde(rename = "Consistent")]
+    Consistent { canonical: String },
+    #[serde(rename = "Both serve")]
+    BothServe,
+    #[serde(rename = "No canonical host")]
+    NoCanonicalHost,
+    #[serde(rename = "Incorrect redirect")]
+    IncorrectRedirect { host: String },
+    #[serde(rename = "Unexpected response")]
+    UnexpectedResponse { host: String, status_code: u16 },
+    #[serde(rename = "Timeout")]
+    Timeout { host: String },
+    #[serde(rename = "Untrusted certificate")]
+    UntrustedCertificate { host: String },
+    #[serde(rename = "Invalid certificate")]
+    InvalidCertificate { host: String },
+    #[serde(rename = "Failed to connect")]
+    FailedConnect { host: String },
+    #[serde(rename = "Error")]
+    Error { host: String },
+}
+
+impl From<WwwResult> for RequestResultTemplate<WwwResultResponseTemplate> {
+    fn from(www_result: WwwResult) -> Self {
+        match www_result {
+            WwwResult::Consistent { canonical } => Self::new(
+                WwwResultResponseTemplate::Consistent { canonical },
+                RequestResultStatus::Correct,
+            ),
+            WwwResult::BothServe => Self::new(
+                WwwResultResponseTemplate::BothServe,
+                RequestResultStatus::NearlyCorrect,
+            ),
+            WwwResult::NoCanonicalHost => Self::new(
+                WwwResultResponseTemplate::NoCanonicalHost,
+                RequestResultStatus::Incorrect,
+            ),
+            WwwResult::IncorrectRedirect { host } => Self::new(
+                WwwResultResponseTemplate::IncorrectRedirect { host },
+                RequestResultStatus::NearlyCorrect,
+            ),
+            WwwResult::UnexpectedResponse { host, status_code } => Self::new(
+                WwwResultResponseTemplate::UnexpectedResponse { host, status_code },
+                RequestResultStatus::Incorrect,
+            ),
+            WwwResult::Timeout { host } => Self::new(
+                WwwResultResponseTemplate::Timeout { host },
+                RequestResultStatus::Incorrect,
+            ),
+            WwwResult::UntrustedCertificate { host } => Self::new(
+                WwwResultResponseTemplate::UntrustedCertificate { host },
+                RequestResultStatus::NearlyCorrect,
+            ),
+            WwwResult::InvalidCertificate { host } => Self::new(
+                WwwResultResponseTemplate::InvalidCertificate { host },
+                RequestResultStatus::NearlyCorrect,
+            ),
+            WwwResult::FailedConnect { host } => Self::new(
+                WwwResultResponseTemplate::FailedConnect { host },
+                RequestResultStatus::Incorrect,
+            ),
+            WwwResult::Error { host } => Self::new(
+                WwwResultResponseTemplate::Error { host },
+                RequestResultStatus::Incorrect,
+            ),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Weights for combining every check into an overall score, loaded from a YAML file such as the
+//! binary's `config/scoring.yaml`, e.g.
+//!
+//! ```yaml
+//! https: 30
+//! page_weight: 0
+//! ```
+//!
+//! Checks that are left out use their default weight, and a weight of 0 leaves the check out of the
+//! score. Checks a team hasn't configured, such as API health, never count towards their score.
+
+use crate::config::{load_yaml, ConfigError};
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Weights {
+    pub http: f64,
+    pub https: f64,
+    pub www: f64,
+    pub health: f64,
+    pub journey: f64,
+    pub websocket: f64,
+    pub protocols: f64,
+    pub hygiene: f64,
+    pub page_weight: f64,
+    pub links: f64,
+    pub security: f64,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self {
+            http: 15.0,
+            https: 25.0,
+            www: 5.0,
+            health: 10.0,
+            journey: 10.0,
+            websocket: 5.0,
+            protocols: 5.0,
+            hygiene: 5.0,
+            page_weight: 5.0,
+            links: 5.0,
+            security: 10.0,
+        }
+    }
+}
+
+impl Weights {
+    /// Uses the default weights if there's no file at `path`
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        load_yaml(path)
+    }
+}
+
+#[must_use]
+pub const fn grade(score: u8) -> &'static str {
+    match score {
+        90.. => "A",
+        80..=89 => "B",
+        70..=79 => "C",
+        60..=69 => "D",
+        _ => "F",
+    }
+}
@@ -0,0 +1,166 @@
+use super::resource::Resource;
+
+/// At most this many assets are fetched per team
+const MAX_ASSETS: usize = 50;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum AssetKind {
+    Script,
+    Stylesheet,
+    Image,
+    Font,
+    Other,
+}
+
+impl AssetKind {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Script => "Script",
+            Self::Stylesheet => "Stylesheet",
+            Self::Image => "Image",
+            Self::Font => "Font",
+            Self::Other => "Other",
+        }
+    }
+
+    fn from_extension(url: &reqwest::Url) -> Self {
+        let extension = url
+            .path()
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("js" | "mjs") => Self::Script,
+            Some("css") => Self::Stylesheet,
+            Some("png" | "jpg" | "jpeg" | "gif" | "svg" | "webp" | "avif" | "ico") => Self::Image,
+            Some("woff" | "woff2" | "ttf" | "otf" | "eot") => Self::Font,
+            _ => Self::Other,
+        }
+    }
+}
+
+pub enum AssetOutcome {
+    Loaded { bytes: usize },
+    Failed { status_code: u16 },
+    Unreachable,
+}
+
+pub struct Asset {
+    pub url: String,
+    pub kind: AssetKind,
+    pub outcome: AssetOutcome,
+}
+
+pub enum AssetsResult {
+    Inventoried {
+        html_bytes: usize,
+        assets: Vec<Asset>,
+        /// Whether there were more same-origin assets than were fetched
+        truncated: bool,
+    },
+    Unavailable,
+}
+
+/// Same-origin assets referenced by the page, in document order without duplicates
+pub(super) fn linked_assets(base: &reqwest::Url, html: &str) -> Vec<(reqwest::Url, AssetKind)> {
+    let document = scraper::Html::parse_document(html);
+    let selectors = [
+        ("script[src]", "src", Some(AssetKind::Script)),
+        (
+            r#"link[rel~="stylesheet"][href]"#,
+            "href",
+            Some(AssetKind::Stylesheet),
+        ),
+        (
+            r#"link[rel~="preload"][as="font"][href]"#,
+            "href",
+            Some(AssetKind::Font),
+        ),
+        (r#"link[rel~="icon"][href]"#, "href", Some(AssetKind::Image)),
+        ("img[src]", "src", Some(AssetKind::Image)),
+        ("source[src], video[src], audio[src]", "src", None),
+    ];
+    let mut assets: Vec<(reqwest::Url, AssetKind)> = vec![];
+    for (selector, attribute, kind) in selectors {
+        let selector = scraper::Selector::parse(selector).unwrap();
+        for element in document.select(&selector) {
+            let Some(url) = element
+                .value()
+                .attr(attribute)
+                .and_then(|link| base.join(link).ok())
+                .filter(|url| url.origin() == base.origin())
+            else {
+                continue;
+            };
+            if !assets.iter().any(|(existing, _)| *existing == url) {
+                let kind = kind.unwrap_or_else(|| AssetKind::from_extension(&url));
+                assets.push((url, kind));
+            }
+        }
+    }
+    assets
+}
+
+/// Same-origin `url(...)` references in a stylesheet, such as fonts and background images
+fn stylesheet_assets(base: &reqwest::Url, css: &str) -> Vec<(reqwest::Url, AssetKind)> {
+    let regex = regex::Regex::new(r#"url\(\s*['"]?([^'")]+)['"]?\s*\)"#).unwrap();
+    regex
+        .captures_iter(css)
+        .filter_map(|captures| base.join(captures[1].trim()).ok())
+        .filter(|url| url.origin() == base.origin() && url.scheme() != "data")
+        .map(|url| {
+            let kind = AssetKind::from_extension(&url);
+            (url, kind)
+        })
+        .collect()
+}
+
+pub(super) async fn assets_check(
+    client: reqwest::Client,
+    homepage: Option<&Resource>,
+) -> AssetsResult {
+    let Some(homepage) = homepage.filter(|homepage| homepage.status.is_success()) else {
+        return AssetsResult::Unavailable;
+    };
+    let Some(html) = &homepage.body else {
+        return AssetsResult::Unavailable;
+    };
+
+    let mut queue = linked_assets(&homepage.url, &String::from_utf8_lossy(html));
+    let mut assets: Vec<Asset> = vec![];
+    let mut index = 0;
+    while let Some((asset_url, kind)) = queue.get(index).cloned() {
+        if assets.len() == MAX_ASSETS {
+            break;
+        }
+        index += 1;
+        let outcome = match Resource::fetch(&client, asset_url.as_str()).await {
+            Ok(asset) if asset.status.is_success() => {
+                if let (AssetKind::Stylesheet, Some(css)) = (kind, &asset.body) {
+                    for linked in stylesheet_assets(&asset_url, &String::from_utf8_lossy(css)) {
+                        if !queue.iter().any(|(existing, _)| *existing == linked.0) {
+                            queue.push(linked);
+                        }
+                    }
+                }
+                AssetOutcome::Loaded {
+                    bytes: asset.transferred_bytes,
+                }
+            }
+            Ok(asset) => AssetOutcome::Failed {
+                status_code: asset.status.as_u16(),
+            },
+            Err(_) => AssetOutcome::Unreachable,
+        };
+        assets.push(Asset {
+            url: asset_url.to_string(),
+            kind,
+            outcome,
+        });
+    }
+
+    AssetsResult::Inventoried {
+        html_bytes: homepage.transferred_bytes,
+        assets,
+        truncated: index < queue.len(),
+    }
+}
@@ -0,0 +1,35 @@
+use super::resource::Resource;
+
+pub enum CapabilitiesResult {
+    Negotiated {
+        /// The ALPN protocol the server chose
+        alpn: &'static str,
+        /// Whether `Alt-Svc` advertises HTTP/3
+        http3: bool,
+        alt_svc: Option<String>,
+    },
+    Unavailable,
+}
+
+/// From the homepage, which is requested over HTTPS offering both `h2` and `http/1.1` through ALPN
+pub(super) fn capabilities_check(homepage: Option<&Resource>) -> CapabilitiesResult {
+    let Some(homepage) = homepage else {
+        return CapabilitiesResult::Unavailable;
+    };
+
+    let alt_svc = homepage.header("Alt-Svc").map(ToOwned::to_owned);
+    CapabilitiesResult::Negotiated {
+        alpn: if homepage.version == reqwest::Version::HTTP_2 {
+            "h2"
+        } else {
+            "http/1.1"
+        },
+        // Covers draft versions such as `h3-29` too
+        http3: alt_svc.as_deref().is_some_and(|alt_svc| {
+            alt_svc
+                .split(',')
+                .any(|service| service.trim_start().starts_with("h3"))
+        }),
+        alt_svc,
+    }
+}
@@ -0,0 +1,230 @@
+use crate::teams::{Team, TEAMS};
+use std::{
+    error::Error,
+    fmt,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+pub(super) const USER_AGENT: &str = "https://uob-team-project-2022.nihaal.dev/";
+const DEFAULT_TIMEOUT: Duration = Duration::from_millis(2000);
+
+/// What to check and how, shared by every check. Build one with [`Checker::builder`]
+///
+/// ```no_run
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::time::Duration;
+/// use uob_team_project_progress_tracker::status::{get_results, Checker};
+///
+/// let checker = Checker::builder().timeout(Duration::from_secs(5)).build()?;
+/// for result in get_results(&checker).await? {
+///     println!("Team {}", result.team.team_number);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Checker {
+    client: reqwest::Client,
+    config: CheckerBuilder,
+}
+
+impl Checker {
+    #[must_use]
+    pub fn builder() -> CheckerBuilder {
+        CheckerBuilder {
+            roster: TEAMS.into(),
+            timeout: DEFAULT_TIMEOUT,
+            root_certificates: vec![],
+            resolve: vec![],
+            proxy: None,
+            replay_fixtures: None,
+            record_fixtures: None,
+            journeys_dir: None,
+        }
+    }
+
+    /// Shared by every check, so connections are reused
+    #[must_use]
+    pub const fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    /// For checks that need a client of their own, e.g. to keep cookies
+    pub(super) fn client_builder(&self) -> reqwest::ClientBuilder {
+        self.config.client_builder()
+    }
+
+    #[must_use]
+    pub fn roster(&self) -> &[Team] {
+        &self.config.roster
+    }
+
+    /// How long each request has to complete
+    #[must_use]
+    pub const fn timeout(&self) -> Duration {
+        self.config.timeout
+    }
+
+    pub(super) fn replay_fixtures(&self) -> Option<&Path> {
+        self.config.replay_fixtures.as_deref()
+    }
+
+    pub(super) fn record_fixtures(&self) -> Option<&Path> {
+        self.config.record_fixtures.as_deref()
+    }
+
+    pub(super) fn journeys_dir(&self) -> Option<&Path> {
+        self.config.journeys_dir.as_deref()
+    }
+
+    /// Where [`CheckerBuilder::resolve`] sends `host`, for checks that connect without the client.
+    /// As with the client, the port is taken from elsewhere rather than the address
+    pub(super) fn resolved(&self, host: &str) -> Option<SocketAddr> {
+        // The client keeps the last address given for a domain
+        self.config
+            .resolve
+            .iter()
+            .rev()
+            .find(|(domain, _)| domain == host)
+            .map(|(_, address)| *address)
+    }
+}
+
+/// Why [`CheckerBuilder::build`] failed
+#[derive(Debug)]
+pub enum BuildError {
+    /// The TLS backend couldn't be initialised
+    Client(reqwest::Error),
+    /// Every check starts from a team's primary endpoint, so a team needs at least one
+    NoEndpoints { team_number: u8 },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Client(e) => write!(f, "failed to build client: {}", e),
+            Self::NoEndpoints { team_number } => {
+                write!(f, "team {} has no endpoints to check", team_number)
+            }
+        }
+    }
+}
+
+impl Error for BuildError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Client(e) => Some(e),
+            Self::NoEndpoints { .. } => None,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CheckerBuilder {
+    roster: Arc<[Team]>,
+    timeout: Duration,
+    root_certificates: Vec<reqwest::Certificate>,
+    resolve: Vec<(String, SocketAddr)>,
+    proxy: Option<reqwest::Proxy>,
+    replay_fixtures: Option<PathBuf>,
+    record_fixtures: Option<PathBuf>,
+    journeys_dir: Option<PathBuf>,
+}
+
+impl CheckerBuilder {
+    /// The teams to check, instead of [`TEAMS`], e.g. a `Vec<Team>` loaded at runtime
+    #[must_use]
+    pub fn roster(mut self, roster: impl Into<Arc<[Team]>>) -> Self {
+        self.roster = roster.into();
+        self
+    }
+
+    /// Defaults to 2 seconds
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Trusts certificates signed by `certificate`, as well as the usual roots. WebSocket checks
+    /// only trust the usual roots
+    #[must_use]
+    pub fn add_root_certificate(mut self, certificate: reqwest::Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Connects to `address` for `domain`, in every check. The port is taken from the URL, or the
+    /// port being audited, rather than `address`
+    #[must_use]
+    pub fn resolve(mut self, domain: &str, address: SocketAddr) -> Self {
+        self.resolve.push((domain.to_owned(), address));
+        self
+    }
+
+    /// e.g. `reqwest::Proxy::all("socks5://127.0.0.1:9090")` to check from inside a network. Only
+    /// requests made with the client go through the proxy, so WebSocket checks and the port audit
+    /// still connect directly
+    #[must_use]
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Classifies the HTTP and HTTPS fixtures recorded in `dir` instead of making those requests,
+    /// as if the network had returned them. Other checks still go to the network
+    #[must_use]
+    pub fn replay_fixtures(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.replay_fixtures = Some(dir.into());
+        self
+    }
+
+    /// Records what each HTTP and HTTPS request for an endpoint got back as a fixture in `dir`, to
+    /// be replayed with [`Self::replay_fixtures`]. Failing to record one is reported as a warning
+    #[must_use]
+    pub fn record_fixtures(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.record_fixtures = Some(dir.into());
+        self
+    }
+
+    /// Runs each team's journeys from `team-XX.yaml` in `dir`. No journeys are run without one
+    #[must_use]
+    pub fn journeys_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.journeys_dir = Some(dir.into());
+        self
+    }
+
+    fn client_builder(&self) -> reqwest::ClientBuilder {
+        let mut builder = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .user_agent(USER_AGENT)
+            .timeout(self.timeout)
+            .min_tls_version(reqwest::tls::Version::TLS_1_2);
+        for certificate in &self.root_certificates {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+        for (domain, address) in &self.resolve {
+            builder = builder.resolve(domain, *address);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        builder
+    }
+
+    /// Fails if the TLS backend can't be initialised, or a team on the roster has no endpoints
+    pub fn build(self) -> Result<Checker, BuildError> {
+        if let Some(team) = self.roster.iter().find(|team| team.endpoints.is_empty()) {
+            return Err(BuildError::NoEndpoints {
+                team_number: team.team_number,
+            });
+        }
+        Ok(Checker {
+            client: self.client_builder().build().map_err(BuildError::Client)?,
+            config: self,
+        })
+    }
+}
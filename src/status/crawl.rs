@@ -0,0 +1,257 @@
+use super::resource::Resource;
+use crate::teams::Endpoint;
+use std::collections::VecDeque;
+
+const MAX_DEPTH: usize = 3;
+const MAX_PAGES: usize = 30;
+const MAX_REDIRECTS: usize = 10;
+
+pub enum LinkProblem {
+    Broken { status_code: u16 },
+    ServerError { status_code: u16 },
+    RedirectLoop,
+    TooManyRedirects,
+    Unreachable,
+}
+
+pub struct BrokenLink {
+    pub url: String,
+    /// The page the link was found on, or `None` for the homepage
+    pub found_on: Option<String>,
+    pub problem: LinkProblem,
+}
+
+pub enum CrawlResult {
+    Crawled {
+        pages: usize,
+        problems: Vec<BrokenLink>,
+        /// Whether the page limit was reached before every link was followed
+        truncated: bool,
+    },
+    Unavailable,
+}
+
+/// The `Allow` and `Disallow` rules from robots.txt that apply to every user agent. Only the `*`
+/// group is honoured: groups naming a user agent, even the checker's, are ignored
+struct Robots {
+    /// Whether each rule allows access, and the pattern it applies to
+    rules: Vec<(bool, String)>,
+}
+
+impl Robots {
+    fn parse(robots_txt: &str) -> Self {
+        let mut rules = vec![];
+        let mut in_group = false;
+        let mut group_applies = false;
+        for line in robots_txt.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((field, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match field.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    // Consecutive user agents share a group
+                    if in_group {
+                        in_group = false;
+                        group_applies = false;
+                    }
+                    group_applies |= value == "*";
+                }
+                "allow" | "disallow" => {
+                    in_group = true;
+                    if group_applies && !value.is_empty() {
+                        rules.push((field.trim().eq_ignore_ascii_case("allow"), value.to_owned()));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Self { rules }
+    }
+
+    /// Matches the path and query, as crawlers do, supporting `*` wildcards and `$` anchors, with
+    /// the longest matching rule winning
+    fn allows(&self, url: &reqwest::Url) -> bool {
+        let target = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_owned(),
+        };
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| {
+                let (pattern, anchored) = match pattern.strip_suffix('$') {
+                    Some(pattern) => (pattern, true),
+                    None => (pattern.as_str(), false),
+                };
+                let regex = format!(
+                    "^{}{}",
+                    pattern
+                        .split('*')
+                        .map(regex::escape)
+                        .collect::<Vec<_>>()
+                        .join(".*"),
+                    if anchored { "$" } else { "" }
+                );
+                regex::Regex::new(&regex).is_ok_and(|regex| regex.is_match(&target))
+            })
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+}
+
+enum Page {
+    Html(reqwest::Url, String),
+    /// Not HTML, or redirected to another site
+    Other,
+}
+
+/// Reuses `homepage` rather than requesting it again
+async fn fetch_page(
+    client: &reqwest::Client,
+    url: &reqwest::Url,
+    homepage: Option<&Resource>,
+) -> Result<Page, LinkProblem> {
+    let mut url = url.clone();
+    let mut chain = vec![];
+    loop {
+        let fetched;
+        let response = match homepage.filter(|homepage| homepage.url == url) {
+            Some(homepage) => homepage,
+            None => {
+                fetched = Resource::fetch(client, url.as_str())
+                    .await
+                    .or(Err(LinkProblem::Unreachable))?;
+                &fetched
+            }
+        };
+        let status_code = response.status.as_u16();
+        if response.status.is_redirection() {
+            let Some(location) = response
+                .header("Location")
+                .and_then(|location| url.join(location).ok())
+            else {
+                return Ok(Page::Other);
+            };
+            chain.push(url);
+            if chain.contains(&location) {
+                return Err(LinkProblem::RedirectLoop);
+            }
+            if chain.len() > MAX_REDIRECTS {
+                return Err(LinkProblem::TooManyRedirects);
+            }
+            if location.host_str() != chain[0].host_str() {
+                return Ok(Page::Other);
+            }
+            url = location;
+        } else if response.status.is_client_error() {
+            return Err(LinkProblem::Broken { status_code });
+        } else if response.status.is_server_error() {
+            return Err(LinkProblem::ServerError { status_code });
+        } else {
+            let is_html = response
+                .header("Content-Type")
+                .is_some_and(|content_type| content_type.starts_with("text/html"));
+            if !is_html {
+                return Ok(Page::Other);
+            }
+            return Ok(Page::Html(url, response.text()));
+        }
+    }
+}
+
+fn page_links(base: &reqwest::Url, html: &str) -> Vec<reqwest::Url> {
+    let document = scraper::Html::parse_document(html);
+    let selector = scraper::Selector::parse("a[href]").unwrap();
+    document
+        .select(&selector)
+        .filter_map(|element| element.value().attr("href"))
+        .filter_map(|link| base.join(link).ok())
+        .filter(|url| matches!(url.scheme(), "http" | "https") && url.host_str() == base.host_str())
+        .map(|mut url| {
+            url.set_fragment(None);
+            url
+        })
+        .collect()
+}
+
+/// Follows same-host links breadth first from the homepage, within depth and page limits
+pub(super) async fn crawl_check(
+    client: reqwest::Client,
+    endpoint: &Endpoint,
+    homepage: Option<&Resource>,
+) -> CrawlResult {
+    let Ok(start) = reqwest::Url::parse(&format!("https://{}{}", endpoint.domain, endpoint.path))
+    else {
+        return CrawlResult::Unavailable;
+    };
+    let robots = match client.get(start.join("/robots.txt").unwrap()).send().await {
+        Ok(response) if response.status().is_success() => {
+            Robots::parse(&response.text().await.unwrap_or_default())
+        }
+        Ok(_) => Robots { rules: vec![] },
+        Err(_) => return CrawlResult::Unavailable,
+    };
+
+    let mut seen = vec![start.clone()];
+    let mut queue: VecDeque<(reqwest::Url, usize, Option<String>)> =
+        VecDeque::from([(start, 0, None)]);
+    let mut pages = 0;
+    let mut problems = vec![];
+    while let Some((url, depth, found_on)) = queue.pop_front() {
+        if pages == MAX_PAGES {
+            queue.push_front((url, depth, found_on));
+            break;
+        }
+        if !robots.allows(&url) {
+            continue;
+        }
+        pages += 1;
+        match fetch_page(&client, &url, homepage).await {
+            Ok(Page::Html(page_url, html)) if depth < MAX_DEPTH => {
+                for link in page_links(&page_url, &html) {
+                    if !seen.contains(&link) {
+                        seen.push(link.clone());
+                        queue.push_back((link, depth + 1, Some(url.to_string())));
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(problem) => problems.push(BrokenLink {
+                url: url.to_string(),
+                found_on,
+                problem,
+            }),
+        }
+    }
+
+    CrawlResult::Crawled {
+        pages,
+        problems,
+        truncated: !queue.is_empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn robots_rules_match_the_path_and_query() {
+        let robots = Robots::parse(
+            "User-agent: *\nDisallow: /search?\nDisallow: /*.pdf$\nAllow: /search?page=\n\n\
+             User-agent: other\nDisallow: /",
+        );
+        for (url, allowed) in [
+            ("https://team.test/", true),
+            ("https://team.test/search", true),
+            ("https://team.test/search?q=team", false),
+            ("https://team.test/search?page=2", true),
+            ("https://team.test/report.pdf", false),
+            ("https://team.test/report.pdf?download", true),
+        ] {
+            let url = reqwest::Url::parse(url).unwrap();
+            assert_eq!(robots.allows(&url), allowed, "{}", url);
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use crate::teams::Endpoint;
+
+/// A path that shouldn't be publicly reachable, and how to tell its real content apart from e.g. a
+/// single-page app serving `index.html` for every path
+struct Probe {
+    name: &'static str,
+    path: &'static str,
+    is_exposed: fn(&str) -> bool,
+}
+
+const PROBES: &[Probe] = &[
+    Probe {
+        name: "Git repository",
+        path: "/.git/HEAD",
+        is_exposed: |body| body.starts_with("ref: refs/"),
+    },
+    Probe {
+        name: "Environment file",
+        path: "/.env",
+        is_exposed: |body| {
+            !body.trim_start().starts_with('<')
+                && body.lines().any(|line| {
+                    line.split_once('=').is_some_and(|(name, _)| {
+                        !name.is_empty()
+                            && name
+                                .chars()
+                                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+                    })
+                })
+        },
+    },
+    Probe {
+        name: "Apache server status",
+        path: "/server-status",
+        is_exposed: |body| body.contains("Apache Server Status"),
+    },
+    Probe {
+        name: "Spring Boot Actuator",
+        path: "/actuator/env",
+        is_exposed: |body| body.contains("\"propertySources\""),
+    },
+    Probe {
+        name: "phpinfo()",
+        path: "/phpinfo.php",
+        is_exposed: |body| body.contains("<title>PHP ") && body.contains("phpinfo()"),
+    },
+    Probe {
+        name: "Django debug page",
+        path: "/uob-team-project-not-found",
+        is_exposed: |body| body.contains("you have <code>DEBUG = True</code>"),
+    },
+    Probe {
+        name: "Werkzeug debugger",
+        path: "/console",
+        is_exposed: |body| body.contains("Werkzeug Debugger"),
+    },
+    Probe {
+        name: "Laravel Ignition",
+        path: "/_ignition/health-check",
+        is_exposed: |body| body.contains("\"can_execute_commands\""),
+    },
+];
+
+pub struct ExposedPath {
+    pub name: &'static str,
+    pub url: String,
+}
+
+pub enum ExposureResult {
+    Probed { exposed: Vec<ExposedPath> },
+    Unavailable,
+}
+
+pub(super) async fn exposure_check(client: reqwest::Client, endpoint: &Endpoint) -> ExposureResult {
+    let mut exposed = vec![];
+    let mut reachable = false;
+    for probe in PROBES {
+        let url = format!("https://{}{}", endpoint.domain, probe.path);
+        let Ok(response) = client.get(&url).send().await else {
+            continue;
+        };
+        reachable = true;
+        // Debug pages are served with error status codes
+        let body = response.text().await.unwrap_or_default();
+        if (probe.is_exposed)(&body) {
+            exposed.push(ExposedPath {
+                name: probe.name,
+                url,
+            });
+        }
+    }
+    if reachable {
+        ExposureResult::Probed { exposed }
+    } else {
+        ExposureResult::Unavailable
+    }
+}
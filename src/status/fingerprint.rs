@@ -0,0 +1,228 @@
+use super::http::set_cookies;
+use super::resource::Resource;
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    Server,
+    Backend,
+    Frontend,
+}
+
+impl Category {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Server => "Server",
+            Self::Backend => "Backend",
+            Self::Frontend => "Frontend",
+        }
+    }
+}
+
+/// What the homepage reveals about how it was built
+struct Evidence {
+    server: String,
+    powered_by: String,
+    cookies: Vec<String>,
+    html: String,
+    /// `src` and `href` of scripts and stylesheets, including other origins
+    asset_paths: Vec<String>,
+}
+
+impl Evidence {
+    fn has_cookie(&self, name: &str) -> bool {
+        self.cookies.iter().any(|cookie| cookie == name)
+    }
+
+    fn has_asset(&self, fragment: &str) -> bool {
+        self.asset_paths.iter().any(|path| path.contains(fragment))
+    }
+}
+
+struct Signature {
+    name: &'static str,
+    category: Category,
+    matches: fn(&Evidence) -> bool,
+}
+
+const SIGNATURES: &[Signature] = &[
+    Signature {
+        name: "nginx",
+        category: Category::Server,
+        matches: |e| e.server.starts_with("nginx"),
+    },
+    Signature {
+        name: "Apache",
+        category: Category::Server,
+        matches: |e| e.server.starts_with("Apache"),
+    },
+    Signature {
+        name: "Caddy",
+        category: Category::Server,
+        matches: |e| e.server.starts_with("Caddy"),
+    },
+    Signature {
+        name: "Cloudflare",
+        category: Category::Server,
+        matches: |e| e.server == "cloudflare",
+    },
+    Signature {
+        name: "Gunicorn",
+        category: Category::Server,
+        matches: |e| e.server.starts_with("gunicorn"),
+    },
+    Signature {
+        name: "Uvicorn",
+        category: Category::Server,
+        matches: |e| e.server.starts_with("uvicorn"),
+    },
+    Signature {
+        name: "Express",
+        category: Category::Backend,
+        matches: |e| e.powered_by == "Express" || e.has_cookie("connect.sid"),
+    },
+    Signature {
+        name: "Django",
+        category: Category::Backend,
+        matches: |e| e.has_cookie("csrftoken") || e.html.contains("csrfmiddlewaretoken"),
+    },
+    Signature {
+        name: "Flask",
+        category: Category::Backend,
+        matches: |e| e.server.starts_with("Werkzeug"),
+    },
+    Signature {
+        name: "Spring",
+        category: Category::Backend,
+        matches: |e| e.has_cookie("JSESSIONID") || e.html.contains("Whitelabel Error Page"),
+    },
+    Signature {
+        name: "Laravel",
+        category: Category::Backend,
+        matches: |e| e.has_cookie("laravel_session"),
+    },
+    Signature {
+        name: "PHP",
+        category: Category::Backend,
+        matches: |e| e.powered_by.starts_with("PHP") || e.has_cookie("PHPSESSID"),
+    },
+    Signature {
+        name: "Ruby on Rails",
+        category: Category::Backend,
+        matches: |e| {
+            e.html
+                .contains(r#"name="csrf-param" content="authenticity_token""#)
+        },
+    },
+    Signature {
+        name: "ASP.NET",
+        category: Category::Backend,
+        matches: |e| e.powered_by == "ASP.NET" || e.has_cookie("ASP.NET_SessionId"),
+    },
+    Signature {
+        name: "Next.js",
+        category: Category::Frontend,
+        matches: |e| e.powered_by == "Next.js" || e.html.contains("__NEXT_DATA__"),
+    },
+    Signature {
+        name: "Nuxt",
+        category: Category::Frontend,
+        matches: |e| e.html.contains("__NUXT__") || e.has_asset("/_nuxt/"),
+    },
+    Signature {
+        name: "React",
+        category: Category::Frontend,
+        matches: |e| {
+            e.html.contains("data-reactroot")
+                || e.html.contains("__NEXT_DATA__")
+                || e.has_asset("react")
+                || (e.html.contains(r#"<div id="root">"#) && e.has_asset("/static/js/main."))
+        },
+    },
+    Signature {
+        name: "Vue",
+        category: Category::Frontend,
+        matches: |e| {
+            e.html.contains("data-v-")
+                || e.html.contains("__NUXT__")
+                || e.has_asset("vue")
+                || (e.html.contains(r#"<div id="app">"#) && e.has_asset("/js/app."))
+        },
+    },
+    Signature {
+        name: "Angular",
+        category: Category::Frontend,
+        matches: |e| e.html.contains("ng-version") || e.html.contains("<app-root"),
+    },
+    Signature {
+        name: "Svelte",
+        category: Category::Frontend,
+        matches: |e| e.html.contains("svelte-") || e.has_asset("/_app/immutable/"),
+    },
+    Signature {
+        name: "Bootstrap",
+        category: Category::Frontend,
+        matches: |e| e.has_asset("bootstrap"),
+    },
+    Signature {
+        name: "jQuery",
+        category: Category::Frontend,
+        matches: |e| e.has_asset("jquery"),
+    },
+];
+
+pub struct Technology {
+    pub name: &'static str,
+    pub category: Category,
+}
+
+pub enum StackResult {
+    Fingerprinted { technologies: Vec<Technology> },
+    Unavailable,
+}
+
+/// Fingerprints the homepage from its headers, cookies, HTML markers and asset paths
+pub(super) fn fingerprint_check(homepage: Option<&Resource>) -> StackResult {
+    let Some(homepage) = homepage else {
+        return StackResult::Unavailable;
+    };
+    let header = |name: &str| homepage.header(name).unwrap_or_default().to_owned();
+    let server = header("Server");
+    let powered_by = header("X-Powered-By");
+    let cookies = set_cookies(&homepage.headers)
+        .into_iter()
+        .map(|cookie| cookie.name)
+        .collect();
+    let html = homepage.text();
+    let asset_paths = {
+        let document = scraper::Html::parse_document(&html);
+        let selector = scraper::Selector::parse("script[src], link[href]").unwrap();
+        document
+            .select(&selector)
+            .filter_map(|element| {
+                element
+                    .value()
+                    .attr("src")
+                    .or_else(|| element.value().attr("href"))
+            })
+            .map(str::to_ascii_lowercase)
+            .collect()
+    };
+    let evidence = Evidence {
+        server,
+        powered_by,
+        cookies,
+        html,
+        asset_paths,
+    };
+
+    StackResult::Fingerprinted {
+        technologies: SIGNATURES
+            .iter()
+            .filter(|signature| (signature.matches)(&evidence))
+            .map(|signature| Technology {
+                name: signature.name,
+                category: signature.category,
+            })
+            .collect(),
+    }
+}
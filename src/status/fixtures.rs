@@ -0,0 +1,257 @@
+//! Fixtures of what endpoints' HTTP and HTTPS requests got back, so their classification can be
+//! tested offline. Only those requests are recorded and replayed: health checks, journeys,
+//! WebSocket checks, the port audit and the checks that fetch pages, e.g. the crawl, still go to
+//! the network. Run with `RECORD_FIXTURES` set to a directory to record them in a sweep, e.g.
+//!
+//! ```sh
+//! RECORD_FIXTURES=fixtures/http cargo run
+//! ```
+//!
+//! and with `REPLAY_FIXTURES` set to classify the fixtures instead of making those requests, e.g.
+//!
+//! ```sh
+//! REPLAY_FIXTURES=fixtures/http cargo run -- check
+//! ```
+//!
+//! Failed requests are recorded as reqwest reported them rather than as a kind of failure, so
+//! replaying them also tests how errors are told apart.
+//!
+//! The fixtures in `fixtures/http` are recorded from the mock teams in `status::tests`, with
+//!
+//! ```sh
+//! rm fixtures/http/*.json
+//! RECORD_FIXTURES=fixtures/http cargo test status::tests
+//! ```
+//!
+//! and the tests replay each of them and check it's classified the same way as when it was
+//! recorded.
+
+use super::http::Outcome;
+use super::{HttpProtocol, ProtocolResult};
+use crate::teams::{Endpoint, Expected};
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+
+#[derive(Serialize, Deserialize)]
+pub(super) struct Fixture {
+    domain: String,
+    path: String,
+    /// Where the endpoint is expected to redirect to, if it has moved
+    redirect_to: Option<String>,
+    protocol: HttpProtocol,
+    attempt: u8,
+    outcome: Outcome,
+    /// How `outcome` was classified when it was recorded
+    result: ProtocolResult,
+}
+
+/// Percent-encodes everything but letters, digits and dots in the domain, port and path, so no two
+/// endpoints share a name, and marks endpoints expected to redirect, as they can share a path with
+/// one that isn't
+fn file_name(endpoint: &Endpoint, protocol: HttpProtocol) -> String {
+    let redirect = match endpoint.expected {
+        Expected::Serve => "",
+        Expected::Redirect { .. } => "-redirect",
+    };
+    let location: String = format!("{}{}", endpoint.domain, endpoint.path)
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || byte == b'.' {
+                char::from(byte).to_string()
+            } else {
+                format!("%{:02X}", byte)
+            }
+        })
+        .collect();
+    format!("{}-{}{}.json", protocol.as_str(), location, redirect)
+}
+
+impl Fixture {
+    /// Writes what `endpoint` got back over `protocol` to `dir`
+    pub(super) fn record(
+        dir: &Path,
+        endpoint: &Endpoint,
+        protocol: HttpProtocol,
+        attempt: u8,
+        outcome: Outcome,
+        result: &ProtocolResult,
+    ) -> io::Result<()> {
+        let fixture = Self {
+            domain: endpoint.domain.to_string(),
+            path: endpoint.path.to_string(),
+            redirect_to: match &endpoint.expected {
+                Expected::Serve => None,
+                Expected::Redirect { to } => Some(to.to_string()),
+            },
+            protocol,
+            attempt,
+            outcome,
+            result: result.clone(),
+        };
+        fs::create_dir_all(dir)?;
+        fs::write(
+            dir.join(file_name(endpoint, protocol)),
+            serde_json::to_string_pretty(&fixture)?,
+        )
+    }
+
+    /// What was recorded for `endpoint` over `protocol`. A fixture that's missing or can't be
+    /// loaded, e.g. as its status code is invalid, is replayed as a failed request saying why
+    pub(super) fn replayed(dir: &Path, endpoint: &Endpoint, protocol: HttpProtocol) -> Outcome {
+        let path = dir.join(file_name(endpoint, protocol));
+        let error = match fs::read_to_string(&path) {
+            Ok(contents) => match serde_json::from_str::<Self>(&contents) {
+                Ok(fixture) => return fixture.outcome,
+                Err(e) => format!("invalid fixture {}: {}", path.display(), e),
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                format!("no fixture recorded at {}", path.display())
+            }
+            Err(e) => format!("failed to read fixture {}: {}", path.display(), e),
+        };
+        Outcome::Failed {
+            is_timeout: false,
+            is_connect: false,
+            error_chain: vec![error],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{http::http_check_attempt, Checker};
+    use super::*;
+
+    const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/http");
+
+    fn fixtures() -> Vec<(String, Fixture)> {
+        let mut fixtures: Vec<_> = fs::read_dir(FIXTURES_DIR)
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                let fixture = serde_json::from_str(&fs::read_to_string(&path).unwrap())
+                    .unwrap_or_else(|e| panic!("invalid fixture {}: {}", path.display(), e));
+                (path.display().to_string(), fixture)
+            })
+            .collect();
+        fixtures.sort_by(|(a, _), (b, _)| a.cmp(b));
+        fixtures
+    }
+
+    /// Replays `fixture` through a checker, as if its endpoint was checked
+    async fn replay(fixture: &Fixture) -> ProtocolResult {
+        let endpoint = Endpoint {
+            name: "Website".into(),
+            domain: fixture.domain.clone().into(),
+            path: fixture.path.clone().into(),
+            expected: match &fixture.redirect_to {
+                None => Expected::Serve,
+                Some(to) => Expected::Redirect {
+                    to: to.clone().into(),
+                },
+            },
+        };
+        let checker = Checker::builder()
+            .replay_fixtures(FIXTURES_DIR)
+            .build()
+            .unwrap();
+        http_check_attempt(&checker, &endpoint, fixture.protocol, fixture.attempt)
+            .await
+            .0
+    }
+
+    #[tokio::test]
+    async fn replayed_fixtures_are_classified_as_recorded() {
+        let fixtures = fixtures();
+        assert!(!fixtures.is_empty(), "no fixtures in {}", FIXTURES_DIR);
+        for (path, fixture) in fixtures {
+            assert_eq!(replay(&fixture).await, fixture.result, "{}", path);
+        }
+    }
+
+    #[tokio::test]
+    async fn fixtures_cover_every_kind_of_failure() {
+        let mut results = vec![];
+        for (_, fixture) in fixtures() {
+            results.push(replay(&fixture).await);
+        }
+        let kinds: Vec<&str> = results
+            .iter()
+            .filter_map(|result| match result {
+                ProtocolResult::Timeout(_) => Some("timeout"),
+                ProtocolResult::UntrustedCertificate(_) => Some("untrusted certificate"),
+                ProtocolResult::InvalidCertificate(_) => Some("invalid certificate"),
+                ProtocolResult::FailedConnect(_) => Some("failed connection"),
+                ProtocolResult::Error(_) => Some("other error"),
+                _ => None,
+            })
+            .collect();
+        for kind in [
+            "timeout",
+            "untrusted certificate",
+            "invalid certificate",
+            "failed connection",
+            "other error",
+        ] {
+            assert!(kinds.contains(&kind), "no fixture for a {}", kind);
+        }
+    }
+
+    #[test]
+    fn file_names_keep_the_port_and_path_apart() {
+        let name = |domain: &'static str, path: &'static str| {
+            let mut endpoint = Endpoint::website(domain);
+            endpoint.path = path.into();
+            file_name(&endpoint, HttpProtocol::Http)
+        };
+        assert_ne!(name("team.test:8080", "/a"), name("team.test", "/a"));
+        assert_ne!(name("team.test", "/a_b"), name("team.test", "/a/b"));
+        assert_ne!(name("team.test", "/a-redirect"), {
+            let mut endpoint = Endpoint::website("team.test");
+            endpoint.path = "/a".into();
+            endpoint.expected = Expected::Redirect {
+                to: "https://team.test/b".into(),
+            };
+            file_name(&endpoint, HttpProtocol::Http)
+        });
+    }
+
+    #[test]
+    fn invalid_status_codes_fail_to_load() {
+        let dir = std::env::temp_dir().join(format!("fixtures-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let endpoint = Endpoint::website("team.test");
+        let json = serde_json::json!({
+            "domain": "team.test",
+            "path": "/",
+            "redirect_to": null,
+            "protocol": "Http",
+            "attempt": 1,
+            "outcome": {
+                "type": "Response",
+                "version": "HTTP/1.1",
+                "status_code": 1000,
+                "headers": [],
+                "body": "",
+            },
+            "result": {"Ok": 200},
+        });
+        fs::write(
+            dir.join(file_name(&endpoint, HttpProtocol::Http)),
+            json.to_string(),
+        )
+        .unwrap();
+        let outcome = Fixture::replayed(&dir, &endpoint, HttpProtocol::Http);
+        fs::remove_dir_all(&dir).unwrap();
+        match outcome {
+            Outcome::Failed { error_chain, .. } => {
+                assert!(
+                    error_chain[0].starts_with("invalid fixture"),
+                    "{:?}",
+                    error_chain
+                );
+            }
+            Outcome::Response { .. } => panic!("loaded a status code of 1000"),
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use super::http::{classify_error, HttpProtocol, RequestError};
+use crate::teams::HealthCheck;
+use serde_json::Value;
+
+pub enum HealthResult {
+    Ok,
+    UnexpectedResponse {
+        status_code: u16,
+    },
+    NotJson {
+        content_type: Option<String>,
+    },
+    InvalidJson,
+    AssertionFailed {
+        assertion: String,
+        actual: Option<Value>,
+    },
+    InvalidAssertion {
+        assertion: String,
+    },
+    Timeout,
+    UntrustedCertificate,
+    InvalidCertificate,
+    FailedConnect,
+    Error,
+}
+
+#[cfg_attr(test, derive(PartialEq, Debug))]
+enum Comparison {
+    Exists,
+    Equals(Value),
+    NotEquals(Value),
+}
+
+pub(super) struct InvalidAssertion;
+
+pub(super) struct Assertion<'a> {
+    path: &'a str,
+    comparison: Comparison,
+}
+
+impl<'a> Assertion<'a> {
+    pub(super) fn parse(assertion: &'a str) -> Result<Self, InvalidAssertion> {
+        let parse_expected =
+            |expected: &str| serde_json::from_str(expected.trim()).or(Err(InvalidAssertion));
+        // The first operator splits the path from the expected value, which can contain either
+        let operator = ["==", "!="]
+            .into_iter()
+            .filter_map(|operator| Some((assertion.find(operator)?, operator)))
+            .min();
+        let (path, comparison) = match operator {
+            Some((index, operator)) => {
+                let expected = parse_expected(&assertion[index + operator.len()..])?;
+                (
+                    &assertion[..index],
+                    if operator == "==" {
+                        Comparison::Equals(expected)
+                    } else {
+                        Comparison::NotEquals(expected)
+                    },
+                )
+            }
+            None => (assertion, Comparison::Exists),
+        };
+        Ok(Self {
+            path: path.trim(),
+            comparison,
+        })
+    }
+
+    /// Returns whether the assertion holds along with the value found at the path
+    pub(super) fn evaluate<'v>(
+        &self,
+        body: &'v Value,
+    ) -> Result<(bool, Option<&'v Value>), InvalidAssertion> {
+        let actual = select(body, self.path)?;
+        let holds = match &self.comparison {
+            Comparison::Exists => actual.is_some(),
+            Comparison::Equals(expected) => actual == Some(expected),
+            Comparison::NotEquals(expected) => actual.is_some() && actual != Some(expected),
+        };
+        Ok((holds, actual))
+    }
+}
+
+/// Selects a value using a subset of JSONPath: `$` followed by any number of `.key`, `["key"]` or
+/// `[index]` segments.
+pub(super) fn select<'v>(
+    value: &'v Value,
+    path: &str,
+) -> Result<Option<&'v Value>, InvalidAssertion> {
+    let mut rest = path.strip_prefix('$').ok_or(InvalidAssertion)?;
+    let mut current = Some(value);
+    while !rest.is_empty() {
+        let key;
+        if let Some(after_dot) = rest.strip_prefix('.') {
+            let end = after_dot.find(['.', '[']).unwrap_or(after_dot.len());
+            key = Value::String(after_dot[..end].to_owned());
+            rest = &after_dot[end..];
+        } else if let Some(after_bracket) = rest.strip_prefix('[') {
+            let (inside, after) = after_bracket.split_once(']').ok_or(InvalidAssertion)?;
+            key = serde_json::from_str(inside.trim()).or(Err(InvalidAssertion))?;
+            rest = after;
+        } else {
+            return Err(InvalidAssertion);
+        }
+        current = match (current, key) {
+            (Some(Value::Object(object)), Value::String(key)) => object.get(&key),
+            (Some(Value::Array(array)), Value::Number(index)) => index
+                .as_u64()
+                .and_then(|index| usize::try_from(index).ok())
+                .and_then(|index| array.get(index)),
+            _ => None,
+        };
+    }
+    Ok(current)
+}
+
+pub(super) async fn health_check(client: reqwest::Client, check: &HealthCheck) -> HealthResult {
+    let response = match client.get(check.url.as_ref()).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            return match classify_error(&e, HttpProtocol::from_url(&check.url)) {
+                RequestError::Timeout => HealthResult::Timeout,
+                RequestError::UntrustedCertificate => HealthResult::UntrustedCertificate,
+                RequestError::InvalidCertificate => HealthResult::InvalidCertificate,
+                RequestError::FailedConnect => HealthResult::FailedConnect,
+                RequestError::Other => HealthResult::Error,
+            };
+        }
+    };
+
+    if response.status().as_u16() != check.status_code {
+        return HealthResult::UnexpectedResponse {
+            status_code: response.status().as_u16(),
+        };
+    }
+
+    let content_type = response
+        .headers()
+        .get("Content-Type")
+        .and_then(|content_type| content_type.to_str().ok())
+        .map(ToOwned::to_owned);
+    if content_type
+        .as_deref()
+        .and_then(|content_type| content_type.split(';').next())
+        .map(str::trim)
+        != Some("application/json")
+    {
+        return HealthResult::NotJson { content_type };
+    }
+
+    let body: Value = match response.bytes().await {
+        Ok(body) => match serde_json::from_slice(&body) {
+            Ok(body) => body,
+            Err(_) => return HealthResult::InvalidJson,
+        },
+        Err(_) => return HealthResult::Error,
+    };
+
+    for assertion in check.assertions.iter() {
+        match Assertion::parse(assertion).and_then(|parsed| parsed.evaluate(&body)) {
+            Err(InvalidAssertion) => {
+                return HealthResult::InvalidAssertion {
+                    assertion: assertion.to_string(),
+                }
+            }
+            Ok((false, actual)) => {
+                return HealthResult::AssertionFailed {
+                    assertion: assertion.to_string(),
+                    actual: actual.cloned(),
+                }
+            }
+            Ok((true, _)) => {}
+        }
+    }
+
+    HealthResult::Ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_assertions() {
+        for (assertion, path, comparison) in [
+            ("$.status", "$.status", Comparison::Exists),
+            (
+                r#"$.status == "ok""#,
+                "$.status",
+                Comparison::Equals(json!("ok")),
+            ),
+            ("$.errors!=0", "$.errors", Comparison::NotEquals(json!(0))),
+            (r#"$.a != "==""#, "$.a", Comparison::NotEquals(json!("=="))),
+            (r#"$.a == "!=""#, "$.a", Comparison::Equals(json!("!="))),
+            (
+                r#"$["a b"] == [1]"#,
+                r#"$["a b"]"#,
+                Comparison::Equals(json!([1])),
+            ),
+        ] {
+            let parsed = Assertion::parse(assertion).ok().expect(assertion);
+            assert_eq!(
+                (parsed.path, parsed.comparison),
+                (path, comparison),
+                "{}",
+                assertion
+            );
+        }
+        for assertion in ["$.status == ok", "$.status !=", "$.a == 1 == 1"] {
+            assert!(Assertion::parse(assertion).is_err(), "{}", assertion);
+        }
+    }
+
+    #[test]
+    fn selects_values() {
+        let body = json!({"a": {"b c": [1, {"d": true}]}, "e": null});
+        for (path, selected) in [
+            ("$", Some(&body)),
+            ("$.e", Some(&Value::Null)),
+            (r#"$.a["b c"][0]"#, Some(&json!(1))),
+            (r#"$["a"]["b c"][1].d"#, Some(&json!(true))),
+            ("$.missing", None),
+            ("$.missing.a", None),
+            (r#"$.a["b c"][2]"#, None),
+            ("$.a[0]", None),
+            (r#"$.a["b c"]["0"]"#, None),
+        ] {
+            assert_eq!(select(&body, path).ok(), Some(selected), "{}", path);
+        }
+        for path in ["a", "$a", r#"$.a["b c""#, "$.a[b]"] {
+            assert!(select(&body, path).is_err(), "{}", path);
+        }
+    }
+}
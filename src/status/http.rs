@@ -1,119 +1,457 @@
-use crate::teams::Team;
-use std::time::Duration;
+use super::{checker::USER_AGENT, fixtures::Fixture, Checker, ProtocolResult};
+use crate::teams::{Endpoint, Expected};
+use serde::{Deserialize, Serialize};
+use std::{error::Error, time::Duration};
 
-const TIMEOUT_MS: u64 = 2000;
+/// How much of a response's body is kept to show teams
+const BODY_PREVIEW_BYTES: usize = 4096;
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum HttpProtocol {
     Http,
     Https,
 }
 
 impl HttpProtocol {
-    const fn as_str(&self) -> &str {
+    #[must_use]
+    pub const fn as_str(&self) -> &str {
         match self {
             Self::Http => "http",
             Self::Https => "https",
         }
     }
+
+    pub(super) fn from_url(url: &str) -> Self {
+        if url.starts_with("https://") {
+            Self::Https
+        } else {
+            Self::Http
+        }
+    }
 }
 
-pub(super) enum HttpRequestResult {
-    Ok(u16),
-    NginxDefaultPage(u16),
-    CorrectRedirect(u16),
-    IncorrectRedirect(u16),
-    UnexpectedResponse(u16),
+/// Why a request failed
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub(super) enum RequestError {
     Timeout,
     UntrustedCertificate,
     InvalidCertificate,
     FailedConnect,
-    Error(reqwest::Error),
+    Other,
+}
+
+/// What went wrong with a failed request, kept so teams can see more than the kind of failure
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub url: String,
+    /// Which run of the checks the request was made in, starting from 1
+    pub attempt: u8,
+    /// The error, followed by each of its causes
+    pub error_chain: Vec<String>,
+}
+
+fn error_chain(error: &reqwest::Error) -> Vec<String> {
+    let mut error_chain = vec![error.to_string()];
+    let mut source = error.source();
+    while let Some(cause) = source {
+        error_chain.push(cause.to_string());
+        source = cause.source();
+    }
+    error_chain
+}
+
+/// The request a check made and the response it got, so teams can reproduce the result
+#[derive(Clone, Serialize)]
+pub struct Exchange {
+    /// An equivalent `curl` command
+    pub curl: String,
+    /// `None` if the request failed
+    pub response: Option<RawResponse>,
 }
 
-pub(super) async fn http_check_protocol(
-    client: reqwest::Client,
-    team: &Team,
+#[derive(Clone, Serialize)]
+pub struct RawResponse {
+    pub status_line: String,
+    pub headers: Vec<(String, String)>,
+    /// Up to the first 4096 bytes of the body
+    pub body: String,
+    pub truncated: bool,
+}
+
+impl RawResponse {
+    fn new(status_line: String, headers: &[(String, String)], body: &str) -> Self {
+        let mut end = body.len().min(BODY_PREVIEW_BYTES);
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        Self {
+            status_line,
+            headers: headers.to_vec(),
+            body: body[..end].to_owned(),
+            truncated: end < body.len(),
+        }
+    }
+}
+
+/// Matches the checker's client, which doesn't follow redirects and requires TLS 1.2
+fn curl_command(url: &str, timeout: Duration) -> String {
+    format!(
+        "curl --include --request GET --user-agent '{}' --max-time {} --tlsv1.2 '{}'",
+        USER_AGENT,
+        timeout.as_secs_f64(),
+        url.replace('\'', r"'\''")
+    )
+}
+
+/// What a request got back, before it's classified, so it can be recorded and replayed
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub(super) enum Outcome {
+    Response {
+        /// e.g. `HTTP/1.1`
+        version: String,
+        #[serde(with = "status_code")]
+        status_code: reqwest::StatusCode,
+        headers: Vec<(String, String)>,
+        body: String,
+    },
+    /// Kept as reqwest reported it, so replaying it runs the same classification as a live request
+    Failed {
+        is_timeout: bool,
+        is_connect: bool,
+        error_chain: Vec<String>,
+    },
+}
+
+/// Status codes as numbers, which fail to load if they're out of range rather than when classified
+mod status_code {
+    use reqwest::StatusCode;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        status_code: &StatusCode,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u16(status_code.as_u16())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<StatusCode, D::Error> {
+        StatusCode::from_u16(u16::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+impl Outcome {
+    async fn fetch(client: reqwest::Client, url: &str) -> Self {
+        match client.get(url).send().await {
+            Ok(response) => Self::Response {
+                version: format!("{:?}", response.version()),
+                status_code: response.status(),
+                headers: response
+                    .headers()
+                    .iter()
+                    .map(|(name, value)| {
+                        (
+                            name.to_string(),
+                            String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                        )
+                    })
+                    .collect(),
+                body: response.text().await.unwrap_or_default(),
+            },
+            Err(e) => Self::Failed {
+                is_timeout: e.is_timeout(),
+                is_connect: e.is_connect(),
+                error_chain: error_chain(&e),
+            },
+        }
+    }
+}
+
+pub(super) fn endpoint_url(endpoint: &Endpoint, protocol: HttpProtocol) -> String {
+    format!(
+        "{}://{}{}",
+        protocol.as_str(),
+        endpoint.domain,
+        endpoint.path
+    )
+}
+
+/// Requests `endpoint` over `protocol` once
+pub async fn http_check_protocol(
+    checker: &Checker,
+    endpoint: &Endpoint,
     protocol: HttpProtocol,
-) -> HttpRequestResult {
-    let url = format!("{}://{}/", protocol.as_str(), team.domain);
-    match client
-        .get(&url)
-        .timeout(Duration::from_millis(TIMEOUT_MS))
-        .send()
-        .await
-    {
-        Ok(response) => {
-            if response.status() == reqwest::StatusCode::OK {
+) -> (ProtocolResult, Exchange) {
+    http_check_attempt(checker, endpoint, protocol, 1).await
+}
+
+/// As [`http_check_protocol`], where `attempt` is which run of the checks this is
+pub(super) async fn http_check_attempt(
+    checker: &Checker,
+    endpoint: &Endpoint,
+    protocol: HttpProtocol,
+    attempt: u8,
+) -> (ProtocolResult, Exchange) {
+    let url = endpoint_url(endpoint, protocol);
+    let (result, response) = match checker.replay_fixtures() {
+        Some(dir) => classify(
+            &Fixture::replayed(dir, endpoint, protocol),
+            endpoint,
+            protocol,
+            attempt,
+        ),
+        None => {
+            let outcome = Outcome::fetch(checker.client().clone(), &url).await;
+            let classified = classify(&outcome, endpoint, protocol, attempt);
+            if let Some(dir) = checker.record_fixtures() {
+                if let Err(e) =
+                    Fixture::record(dir, endpoint, protocol, attempt, outcome, &classified.0)
+                {
+                    eprintln!("Warning: failed to record fixture for {}: {}", url, e);
+                }
+            }
+            classified
+        }
+    };
+    (
+        result,
+        Exchange {
+            curl: curl_command(&url, checker.timeout()),
+            response,
+        },
+    )
+}
+
+/// Classifies what a request got back, without making any requests
+pub(super) fn classify(
+    outcome: &Outcome,
+    endpoint: &Endpoint,
+    protocol: HttpProtocol,
+    attempt: u8,
+) -> (ProtocolResult, Option<RawResponse>) {
+    let redirect_target = match &endpoint.expected {
+        Expected::Serve => format!("https://{}{}", endpoint.domain, endpoint.path),
+        Expected::Redirect { to } => to.to_string(),
+    };
+    let mut response = None;
+    let result = match outcome {
+        Outcome::Response {
+            version,
+            status_code,
+            headers,
+            body,
+        } => {
+            let status = *status_code;
+            let location = headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("Location"))
+                .map(|(_, value)| value.as_str());
+            response = Some(RawResponse::new(
+                format!("{} {}", version, status),
+                headers,
+                body,
+            ));
+            if status == reqwest::StatusCode::OK {
                 // OK
-                let status_code = response.status().as_u16();
-                if response.text().await.unwrap().contains(
+                let status_code = status.as_u16();
+                if body.contains(
                     "<p>If you see this page, the nginx web server is successfully installed and\nworking. Further configuration is required.</p>",
                 ) {
-                    HttpRequestResult::NginxDefaultPage(status_code)
+                    ProtocolResult::NginxDefaultPage(status_code)
                 } else {
-                    HttpRequestResult::Ok(status_code)
+                    let insecure_urls = match protocol {
+                        HttpProtocol::Https => mixed_content(body),
+                        HttpProtocol::Http => vec![],
+                    };
+                    if insecure_urls.is_empty() {
+                        ProtocolResult::Ok(status_code)
+                    } else {
+                        // Mixed content
+                        ProtocolResult::MixedContent(status_code, insecure_urls)
+                    }
                 }
             } else if matches!(
-                response.status(),
+                status,
                 reqwest::StatusCode::MOVED_PERMANENTLY
                     | reqwest::StatusCode::PERMANENT_REDIRECT
                     | reqwest::StatusCode::FOUND
-            ) && protocol == HttpProtocol::Http
-                && response.headers().contains_key("Location")
+            ) && (protocol == HttpProtocol::Http
+                || matches!(endpoint.expected, Expected::Redirect { .. }))
+                && location.is_some()
             {
-                // Redirect from HTTP
-                if response
-                    .headers()
-                    .get("Location")
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    == format!("https://{}/", team.domain)
-                {
+                // Redirect from HTTP, or away from a moved endpoint
+                if location == Some(redirect_target.as_str()) {
                     // Redirects correctly
-                    HttpRequestResult::CorrectRedirect(response.status().as_u16())
+                    ProtocolResult::CorrectRedirect(status.as_u16())
                 } else {
                     // Redirects incorrectly
-                    HttpRequestResult::IncorrectRedirect(response.status().as_u16())
+                    ProtocolResult::IncorrectRedirect(status.as_u16())
                 }
-            } else if response.status() == reqwest::StatusCode::FOUND
-                && response.headers().contains_key("Location")
-                && response
-                    .headers()
-                    .get("Location")
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .starts_with('/')
+            } else if status == reqwest::StatusCode::FOUND
+                && location.is_some_and(|location| location.starts_with('/'))
             {
                 // Local redirect
-                HttpRequestResult::Ok(response.status().as_u16())
+                ProtocolResult::Ok(status.as_u16())
             } else {
-                HttpRequestResult::UnexpectedResponse(response.status().as_u16())
+                ProtocolResult::UnexpectedResponse(status.as_u16())
             }
         }
-        Err(e) => {
-            if e.is_timeout() {
-                eprintln!("Timeout: {}", url);
-                HttpRequestResult::Timeout
-            } else if e.is_connect() {
-                if protocol == HttpProtocol::Https
-                    && (format!("{}", e).contains("CertNotValidForName")
-                        || format!("{}", e).contains("UnrecognisedName"))
-                {
-                    HttpRequestResult::InvalidCertificate
-                } else if protocol == HttpProtocol::Https
-                    && format!("{}", e)
-                        .contains("invalid peer certificate contents: invalid peer certificate:")
-                {
-                    HttpRequestResult::UntrustedCertificate
-                } else {
-                    HttpRequestResult::FailedConnect
+        Outcome::Failed {
+            is_timeout,
+            is_connect,
+            error_chain,
+        } => {
+            let diagnostic = Diagnostic {
+                url: endpoint_url(endpoint, protocol),
+                attempt,
+                error_chain: error_chain.clone(),
+            };
+            match request_error(*is_timeout, *is_connect, error_chain, protocol) {
+                RequestError::Timeout => ProtocolResult::Timeout(diagnostic),
+                RequestError::UntrustedCertificate => {
+                    ProtocolResult::UntrustedCertificate(diagnostic)
                 }
+                RequestError::InvalidCertificate => ProtocolResult::InvalidCertificate(diagnostic),
+                RequestError::FailedConnect => ProtocolResult::FailedConnect(diagnostic),
+                RequestError::Other => ProtocolResult::Error(diagnostic),
+            }
+        }
+    };
+    (result, response)
+}
+
+/// Subresources and form actions that would be loaded or submitted over plain HTTP, which browsers
+/// block or warn about on HTTPS pages
+fn mixed_content(html: &str) -> Vec<String> {
+    let document = scraper::Html::parse_document(html);
+    let selectors = [
+        ("script[src]", "src"),
+        ("link[href]", "href"),
+        ("img[src]", "src"),
+        ("img[srcset], source[srcset]", "srcset"),
+        ("iframe[src]", "src"),
+        ("source[src], video[src], audio[src], embed[src]", "src"),
+        ("object[data]", "data"),
+        ("form[action]", "action"),
+    ];
+    let mut urls: Vec<String> = vec![];
+    for (selector, attribute) in selectors {
+        let selector = scraper::Selector::parse(selector).unwrap();
+        for element in document.select(&selector) {
+            // Only stylesheets and icons are loaded by `<link>`, not e.g. canonical URLs
+            if element.value().name() == "link"
+                && !element.value().attr("rel").is_some_and(|rel| {
+                    rel.split_ascii_whitespace()
+                        .any(|rel| matches!(rel, "stylesheet" | "icon" | "preload"))
+                })
+            {
+                continue;
+            }
+            let Some(value) = element.value().attr(attribute) else {
+                continue;
+            };
+            // `srcset` is a comma separated list of URLs with sizes
+            let candidates: Vec<&str> = if attribute == "srcset" {
+                value
+                    .split(',')
+                    .filter_map(|candidate| candidate.split_ascii_whitespace().next())
+                    .collect()
             } else {
-                HttpRequestResult::Error(e)
+                vec![value.trim()]
+            };
+            for url in candidates {
+                if url
+                    .get(..7)
+                    .is_some_and(|scheme| scheme.eq_ignore_ascii_case("http://"))
+                    && !urls.iter().any(|existing| existing == url)
+                {
+                    urls.push(url.to_owned());
+                }
             }
         }
     }
+    urls
+}
+
+/// The security-relevant parts of a `Set-Cookie` header
+pub(super) struct SetCookie {
+    pub name: String,
+    pub secure: bool,
+    pub http_only: bool,
+    pub same_site: bool,
+}
+
+impl SetCookie {
+    /// Whether the cookie's name suggests it identifies a logged in user
+    pub fn is_session(&self) -> bool {
+        let name = self.name.to_ascii_lowercase();
+        ["sess", "sid", "auth", "token", "jwt", "remember"]
+            .iter()
+            .any(|fragment| name.contains(fragment))
+    }
+}
+
+pub(super) fn set_cookies(headers: &reqwest::header::HeaderMap) -> Vec<SetCookie> {
+    headers
+        .get_all("Set-Cookie")
+        .iter()
+        .filter_map(|header| header.to_str().ok())
+        .filter_map(|header| {
+            let mut attributes = header.split(';').map(str::trim);
+            let (name, _) = attributes.next()?.split_once('=')?;
+            let mut cookie = SetCookie {
+                name: name.trim().to_owned(),
+                secure: false,
+                http_only: false,
+                same_site: false,
+            };
+            for attribute in attributes {
+                let name = attribute.split('=').next().unwrap_or_default().trim();
+                match name.to_ascii_lowercase().as_str() {
+                    "secure" => cookie.secure = true,
+                    "httponly" => cookie.http_only = true,
+                    "samesite" => cookie.same_site = true,
+                    _ => {}
+                }
+            }
+            Some(cookie)
+        })
+        .collect()
+}
+
+pub(super) fn classify_error(e: &reqwest::Error, protocol: HttpProtocol) -> RequestError {
+    request_error(e.is_timeout(), e.is_connect(), &error_chain(e), protocol)
+}
+
+/// Classifies a failed request from what reqwest reported, which is all a fixture has to go on
+fn request_error(
+    is_timeout: bool,
+    is_connect: bool,
+    error_chain: &[String],
+    protocol: HttpProtocol,
+) -> RequestError {
+    if is_timeout {
+        RequestError::Timeout
+    } else if is_connect {
+        match protocol {
+            HttpProtocol::Https => error_chain
+                .iter()
+                .find_map(|error| certificate_error(error))
+                .unwrap_or(RequestError::FailedConnect),
+            HttpProtocol::Http => RequestError::FailedConnect,
+        }
+    } else {
+        RequestError::Other
+    }
+}
+
+/// Classifies certificate errors from rustls by their message, as they are only exposed as strings
+pub(super) fn certificate_error(message: &str) -> Option<RequestError> {
+    if message.contains("CertNotValidForName") || message.contains("UnrecognisedName") {
+        Some(RequestError::InvalidCertificate)
+    } else if message.contains("invalid peer certificate contents: invalid peer certificate:") {
+        Some(RequestError::UntrustedCertificate)
+    } else {
+        None
+    }
 }
@@ -0,0 +1,86 @@
+use super::assets::{linked_assets, AssetKind};
+use super::resource::Resource;
+
+/// Only the first few scripts and stylesheets are audited
+const MAX_ASSETS: usize = 3;
+
+pub struct ResourceHygiene {
+    pub url: String,
+    pub content_encoding: Option<String>,
+    pub transferred_bytes: usize,
+    /// `None` if the body couldn't be decompressed
+    pub uncompressed_bytes: Option<usize>,
+    pub cache_control: Option<String>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl ResourceHygiene {
+    #[must_use]
+    pub const fn is_compressed(&self) -> bool {
+        self.content_encoding.is_some()
+    }
+
+    #[must_use]
+    pub fn is_cacheable(&self) -> bool {
+        self.cache_control.as_deref().is_some_and(|cache_control| {
+            cache_control.contains("max-age")
+                && !cache_control.contains("no-store")
+                && !cache_control.contains("max-age=0")
+        })
+    }
+
+    #[must_use]
+    pub const fn is_revalidatable(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+}
+
+pub enum HygieneResult {
+    Audited {
+        html: ResourceHygiene,
+        assets: Vec<ResourceHygiene>,
+    },
+    Unavailable,
+}
+
+impl ResourceHygiene {
+    fn new(resource: &Resource) -> Self {
+        let header = |name| resource.header(name).map(ToOwned::to_owned);
+        Self {
+            url: resource.url.to_string(),
+            content_encoding: resource.content_encoding().map(ToOwned::to_owned),
+            transferred_bytes: resource.transferred_bytes,
+            uncompressed_bytes: resource.body.as_ref().map(Vec::len),
+            cache_control: header("Cache-Control"),
+            etag: header("ETag"),
+            last_modified: header("Last-Modified"),
+        }
+    }
+}
+
+pub(super) async fn hygiene_check(
+    client: reqwest::Client,
+    homepage: Option<&Resource>,
+) -> HygieneResult {
+    let Some(homepage) = homepage.filter(|homepage| homepage.status.is_success()) else {
+        return HygieneResult::Unavailable;
+    };
+
+    let mut assets = vec![];
+    for (asset_url, _) in linked_assets(&homepage.url, &homepage.text())
+        .into_iter()
+        .filter(|(_, kind)| matches!(kind, AssetKind::Script | AssetKind::Stylesheet))
+        .take(MAX_ASSETS)
+    {
+        match Resource::fetch(&client, asset_url.as_str()).await {
+            Ok(asset) if asset.status.is_success() => assets.push(ResourceHygiene::new(&asset)),
+            _ => {}
+        }
+    }
+
+    HygieneResult::Audited {
+        html: ResourceHygiene::new(homepage),
+        assets,
+    }
+}
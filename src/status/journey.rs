@@ -0,0 +1,365 @@
+//! Scripted multi-step journeys, loaded from `team-XX.yaml` in the checker's journeys directory
+//! (`journeys/` for the binary), e.g.
+//!
+//! ```yaml
+//! - name: Log in
+//!   steps:
+//!     - name: Login page
+//!       url: https://example.bham.team/login
+//!       capture:
+//!         csrf:
+//!           regex: 'name="csrf" value="([^"]+)"'
+//!     - name: Submit login
+//!       method: POST
+//!       url: https://example.bham.team/login
+//!       form:
+//!         username: staff
+//!         password: hunter2
+//!         csrf: ${csrf}
+//!       follow_redirects: true
+//!       expect:
+//!         status: 200
+//!         body_contains: [Dashboard]
+//!     - name: Profile API
+//!       url: https://example.bham.team/api/me
+//!       expect:
+//!         json: ['$.username == "staff"']
+//! ```
+//!
+//! Cookies persist between the steps of a journey, and variables captured by a step can be used as
+//! `${name}` in the URL, headers, form and body of later steps.
+//!
+//! The binary's `journeys/` is ignored by git, as journeys can contain login details.
+
+use super::health::{select, Assertion, InvalidAssertion};
+use super::http::{classify_error, HttpProtocol, RequestError};
+use serde::Deserialize;
+use serde_json::Value;
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+const MAX_REDIRECTS: usize = 10;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Journey {
+    pub name: String,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    pub name: String,
+    #[serde(default = "Step::default_method")]
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub form: Option<BTreeMap<String, String>>,
+    pub body: Option<String>,
+    #[serde(default)]
+    pub follow_redirects: bool,
+    #[serde(default)]
+    pub expect: Expect,
+    #[serde(default, with = "serde_yaml::with::singleton_map_recursive")]
+    pub capture: BTreeMap<String, Capture>,
+}
+
+impl Step {
+    fn default_method() -> String {
+        "GET".to_owned()
+    }
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expect {
+    /// Defaults to any status code below 400
+    pub status: Option<u16>,
+    #[serde(default)]
+    pub body_contains: Vec<String>,
+    /// JSONPath-style assertions, as used by API health checks
+    #[serde(default)]
+    pub json: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capture {
+    /// The first capture group of a regex matched against the body
+    Regex(String),
+    /// A JSONPath-style path into the body
+    Json(String),
+    Header(String),
+}
+
+pub struct JourneyResult {
+    pub name: String,
+    /// Steps are run until the first failure, so only the last step can have failed
+    pub steps: Vec<StepResult>,
+}
+
+pub struct StepResult {
+    pub name: String,
+    pub method: String,
+    /// As written in the journey, so captured variables such as tokens aren't shown
+    pub url: String,
+    pub outcome: StepOutcome,
+}
+
+pub enum StepOutcome {
+    Passed {
+        status_code: u16,
+    },
+    UnexpectedResponse {
+        status_code: u16,
+    },
+    MissingText {
+        text: String,
+    },
+    AssertionFailed {
+        assertion: String,
+        actual: Option<Value>,
+    },
+    InvalidAssertion {
+        assertion: String,
+    },
+    CaptureFailed {
+        variable: String,
+    },
+    UndefinedVariable {
+        variable: String,
+    },
+    /// The journey file couldn't be read or parsed
+    InvalidJourney {
+        error: String,
+    },
+    InvalidRequest,
+    TooManyRedirects,
+    Timeout,
+    UntrustedCertificate,
+    InvalidCertificate,
+    FailedConnect,
+    Error,
+}
+
+/// Returns no journeys if the team doesn't have a journey file, and a failed journey if it can't be
+/// read, so one team's mistake doesn't stop the others being checked
+pub(super) fn load_journeys(dir: &Path, team_number: u8) -> Result<Vec<Journey>, JourneyResult> {
+    let path = dir.join(format!("team-{:02}.yaml", team_number));
+    let error = match fs::read_to_string(&path) {
+        Ok(contents) => match serde_yaml::from_str(&contents) {
+            Ok(journeys) => return Ok(journeys),
+            Err(e) => format!("invalid journey file: {}", e),
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => format!("failed to read journey file: {}", e),
+    };
+    let path = path.display().to_string();
+    Err(JourneyResult {
+        name: path.clone(),
+        steps: vec![StepResult {
+            name: "Load journeys".to_owned(),
+            method: String::new(),
+            url: path,
+            outcome: StepOutcome::InvalidJourney { error },
+        }],
+    })
+}
+
+/// Replaces each `${name}` with the captured variable
+fn substitute(template: &str, variables: &BTreeMap<String, String>) -> Result<String, StepOutcome> {
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        let Some(length) = rest[start..].find('}') else {
+            break;
+        };
+        let variable = &rest[start + 2..start + length];
+        output.push_str(&rest[..start]);
+        output.push_str(
+            variables
+                .get(variable)
+                .ok_or_else(|| StepOutcome::UndefinedVariable {
+                    variable: variable.to_owned(),
+                })?,
+        );
+        rest = &rest[start + length + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn error_outcome(e: reqwest::Error, url: &str) -> StepOutcome {
+    match classify_error(&e, HttpProtocol::from_url(url)) {
+        RequestError::Timeout => StepOutcome::Timeout,
+        RequestError::UntrustedCertificate => StepOutcome::UntrustedCertificate,
+        RequestError::InvalidCertificate => StepOutcome::InvalidCertificate,
+        RequestError::FailedConnect => StepOutcome::FailedConnect,
+        RequestError::Other => StepOutcome::Error,
+    }
+}
+
+async fn run_step(
+    client: &reqwest::Client,
+    step: &Step,
+    url: &str,
+    variables: &mut BTreeMap<String, String>,
+) -> Result<u16, StepOutcome> {
+    let method =
+        reqwest::Method::from_bytes(step.method.as_bytes()).or(Err(StepOutcome::InvalidRequest))?;
+    let mut request = client.request(method, url);
+    for (name, value) in &step.headers {
+        request = request.header(name, substitute(value, variables)?);
+    }
+    if let Some(form) = &step.form {
+        let form = form
+            .iter()
+            .map(|(name, value)| Ok((name, substitute(value, variables)?)))
+            .collect::<Result<BTreeMap<_, _>, StepOutcome>>()?;
+        request = request.form(&form);
+    }
+    if let Some(body) = &step.body {
+        request = request.body(substitute(body, variables)?);
+    }
+
+    let mut response = request.send().await.map_err(|e| error_outcome(e, url))?;
+    let mut redirects = 0;
+    while step.follow_redirects && response.status().is_redirection() {
+        let Some(location) = response
+            .headers()
+            .get("Location")
+            .and_then(|location| location.to_str().ok())
+            .and_then(|location| response.url().join(location).ok())
+        else {
+            break;
+        };
+        if redirects == MAX_REDIRECTS {
+            return Err(StepOutcome::TooManyRedirects);
+        }
+        redirects += 1;
+        response = client
+            .get(location.clone())
+            .send()
+            .await
+            .map_err(|e| error_outcome(e, location.as_str()))?;
+    }
+
+    let status_code = response.status().as_u16();
+    if step
+        .expect
+        .status
+        .map_or(status_code >= 400, |expected| expected != status_code)
+    {
+        return Err(StepOutcome::UnexpectedResponse { status_code });
+    }
+
+    let headers = response.headers().clone();
+    let body = response.text().await.or(Err(StepOutcome::Error))?;
+    for text in &step.expect.body_contains {
+        if !body.contains(text.as_str()) {
+            return Err(StepOutcome::MissingText { text: text.clone() });
+        }
+    }
+
+    let json = || serde_json::from_str::<Value>(&body).ok();
+    if !step.expect.json.is_empty() {
+        let json = json().unwrap_or(Value::Null);
+        for assertion in &step.expect.json {
+            match Assertion::parse(assertion).and_then(|parsed| parsed.evaluate(&json)) {
+                Err(InvalidAssertion) => {
+                    return Err(StepOutcome::InvalidAssertion {
+                        assertion: assertion.clone(),
+                    })
+                }
+                Ok((false, actual)) => {
+                    return Err(StepOutcome::AssertionFailed {
+                        assertion: assertion.clone(),
+                        actual: actual.cloned(),
+                    })
+                }
+                Ok((true, _)) => {}
+            }
+        }
+    }
+
+    for (variable, capture) in &step.capture {
+        let captured = match capture {
+            Capture::Regex(regex) => regex::Regex::new(regex)
+                .ok()
+                .and_then(|regex| regex.captures(&body))
+                .and_then(|captures| captures.get(1))
+                .map(|capture| capture.as_str().to_owned()),
+            Capture::Json(path) => json().and_then(|json| match select(&json, path) {
+                Ok(Some(Value::String(value))) => Some(value.clone()),
+                Ok(Some(value)) => Some(value.to_string()),
+                Ok(None) | Err(InvalidAssertion) => None,
+            }),
+            Capture::Header(name) => headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToOwned::to_owned),
+        };
+        match captured {
+            Some(value) => {
+                variables.insert(variable.clone(), value);
+            }
+            None => {
+                return Err(StepOutcome::CaptureFailed {
+                    variable: variable.clone(),
+                })
+            }
+        }
+    }
+
+    Ok(status_code)
+}
+
+/// Runs each step in order with its own cookie jar, stopping at the first failure
+pub(super) async fn run_journey(
+    client_builder: reqwest::ClientBuilder,
+    journey: &Journey,
+) -> JourneyResult {
+    let Ok(client) = client_builder.cookie_store(true).build() else {
+        // None of the steps can run, so the first is failed
+        return JourneyResult {
+            name: journey.name.clone(),
+            steps: journey
+                .steps
+                .first()
+                .map(|step| StepResult {
+                    name: step.name.clone(),
+                    method: step.method.clone(),
+                    url: step.url.clone(),
+                    outcome: StepOutcome::Error,
+                })
+                .into_iter()
+                .collect(),
+        };
+    };
+    let mut variables = BTreeMap::new();
+    let mut steps = vec![];
+    for step in &journey.steps {
+        let outcome = match substitute(&step.url, &variables) {
+            Ok(url) => match run_step(&client, step, &url, &mut variables).await {
+                Ok(status_code) => StepOutcome::Passed { status_code },
+                Err(outcome) => outcome,
+            },
+            Err(outcome) => outcome,
+        };
+        let passed = matches!(outcome, StepOutcome::Passed { .. });
+        steps.push(StepResult {
+            name: step.name.clone(),
+            method: step.method.clone(),
+            url: step.url.clone(),
+            outcome,
+        });
+        if !passed {
+            break;
+        }
+    }
+    JourneyResult {
+        name: journey.name.clone(),
+        steps,
+    }
+}
@@ -1,97 +1,306 @@
+mod assets;
+mod capabilities;
+mod checker;
+mod crawl;
+mod exposure;
+mod fingerprint;
+mod fixtures;
+mod health;
 mod http;
+mod hygiene;
+mod journey;
+mod ports;
+mod resource;
+mod security;
+#[cfg(test)]
+mod tests;
+mod websocket;
+mod www;
 
-use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fmt, time::Duration};
 
-use tokio::task::JoinSet;
+use tokio::task::{JoinError, JoinSet};
 
-use crate::teams::{Team, TEAMS};
+use crate::teams::{Endpoint, HealthCheck, Team, WebSocketCheck};
 
-pub use self::http::HttpProtocol;
-use self::http::{http_check_protocol, HttpRequestResult};
+use self::assets::assets_check;
+pub use self::assets::{Asset, AssetOutcome, AssetsResult};
+use self::capabilities::capabilities_check;
+pub use self::capabilities::CapabilitiesResult;
+pub use self::checker::{BuildError, Checker, CheckerBuilder};
+use self::crawl::crawl_check;
+pub use self::crawl::{BrokenLink, CrawlResult, LinkProblem};
+use self::exposure::exposure_check;
+pub use self::exposure::{ExposedPath, ExposureResult};
+use self::fingerprint::fingerprint_check;
+pub use self::fingerprint::StackResult;
+use self::health::health_check;
+pub use self::health::HealthResult;
+use self::http::http_check_attempt;
+pub use self::http::{http_check_protocol, Diagnostic, Exchange, HttpProtocol, RawResponse};
+use self::hygiene::hygiene_check;
+pub use self::hygiene::{HygieneResult, ResourceHygiene};
+use self::journey::{load_journeys, run_journey};
+pub use self::journey::{JourneyResult, StepOutcome, StepResult};
+use self::ports::ports_check;
+pub use self::ports::{OpenPort, PortsResult};
+use self::resource::fetch_homepage;
+use self::security::security_check;
+pub use self::security::{SecurityFinding, SecurityResult, Severity};
+use self::websocket::websocket_check;
+pub use self::websocket::WebSocketResult;
+use self::www::www_check;
+pub use self::www::WwwResult;
 
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum ProtocolResult {
     Ok(u16),
     NginxDefaultPage(u16),
+    MixedContent(u16, Vec<String>),
     CorrectRedirect(u16),
     IncorrectRedirect(u16),
     UnexpectedResponse(u16),
-    Timeout,
-    UntrustedCertificate,
-    InvalidCertificate,
-    FailedConnect,
-    Error(reqwest::Error),
+    Timeout(Diagnostic),
+    UntrustedCertificate(Diagnostic),
+    InvalidCertificate(Diagnostic),
+    FailedConnect(Diagnostic),
+    Error(Diagnostic),
 }
 
-pub struct TeamResult {
-    pub team: Team,
+pub struct EndpointResult {
+    pub endpoint: Endpoint,
     pub http: ProtocolResult,
     pub https: ProtocolResult,
+    pub http_exchange: Exchange,
+    pub https_exchange: Exchange,
+}
+
+pub struct HealthCheckResult {
+    pub check: HealthCheck,
+    pub result: HealthResult,
+}
+
+pub struct WebSocketCheckResult {
+    pub check: WebSocketCheck,
+    pub result: WebSocketResult,
+}
+
+pub struct TeamResult {
+    pub team: Team,
+    pub endpoints: Vec<EndpointResult>,
+    pub health_checks: Vec<HealthCheckResult>,
+    pub journeys: Vec<JourneyResult>,
+    pub websockets: Vec<WebSocketCheckResult>,
+    pub www: WwwResult,
+    pub capabilities: CapabilitiesResult,
+    pub hygiene: HygieneResult,
+    pub assets: AssetsResult,
+    pub crawl: CrawlResult,
+    pub security: SecurityResult,
+    /// Only shown to staff, as it lists where to find leaked credentials
+    pub exposure: ExposureResult,
+    pub stack: StackResult,
+    /// `None` if the team hasn't opted in to the port audit. Only shown to staff
+    pub ports: Option<PortsResult>,
+}
+
+/// Checks every endpoint of `team` over HTTP and HTTPS, all at once so slow endpoints overlap
+pub async fn endpoint_results(checker: &Checker, team: &Team) -> Vec<EndpointResult> {
+    endpoint_results_attempt(checker, team, 1).await
+}
+
+async fn endpoint_results_attempt(
+    checker: &Checker,
+    team: &Team,
+    attempt: u8,
+) -> Vec<EndpointResult> {
+    futures_util::future::join_all(team.endpoints.iter().map(|endpoint| async move {
+        let ((http_result, http_exchange), (https_result, https_exchange)) = tokio::join!(
+            http_check_attempt(checker, endpoint, HttpProtocol::Http, attempt),
+            http_check_attempt(checker, endpoint, HttpProtocol::Https, attempt)
+        );
+        EndpointResult {
+            endpoint: endpoint.clone(),
+            http: http_result,
+            https: https_result,
+            http_exchange,
+            https_exchange,
+        }
+    }))
+    .await
+}
+
+/// Why [`get_results`] couldn't check the roster
+#[derive(Debug)]
+pub enum CheckError {
+    /// Every endpoint was still unreachable after trying again, which usually means the checker
+    /// is offline rather than every team
+    Unreachable,
+    /// Checking a team panicked
+    Panicked(JoinError),
+}
+
+impl fmt::Display for CheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unreachable => write!(f, "every endpoint is unreachable"),
+            Self::Panicked(e) => write!(f, "checking a team panicked: {}", e),
+        }
+    }
 }
 
-impl From<HttpRequestResult> for ProtocolResult {
-    fn from(result: HttpRequestResult) -> Self {
-        match result {
-            HttpRequestResult::Ok(status_code) => Self::Ok(status_code),
-            HttpRequestResult::NginxDefaultPage(status_code) => Self::NginxDefaultPage(status_code),
-            HttpRequestResult::CorrectRedirect(status_code) => Self::CorrectRedirect(status_code),
-            HttpRequestResult::IncorrectRedirect(status_code) => {
-                Self::IncorrectRedirect(status_code)
-            }
-            HttpRequestResult::UnexpectedResponse(status_code) => {
-                Self::UnexpectedResponse(status_code)
-            }
-            HttpRequestResult::Timeout => Self::Timeout,
-            HttpRequestResult::UntrustedCertificate => Self::UntrustedCertificate,
-            HttpRequestResult::InvalidCertificate => Self::InvalidCertificate,
-            HttpRequestResult::FailedConnect => Self::FailedConnect,
-            HttpRequestResult::Error(error) => Self::Error(error),
+impl Error for CheckError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Unreachable => None,
+            Self::Panicked(e) => Some(e),
         }
     }
 }
 
-pub async fn get_results() -> Vec<TeamResult> {
-    for _ in 0..2 {
+/// Checks every team on the roster, trying again if every endpoint is unreachable, as that's
+/// usually the network the checker is on
+pub async fn get_results(checker: &Checker) -> Result<Vec<TeamResult>, CheckError> {
+    for attempt in 1..=2 {
         let mut results = vec![];
         let mut join_set = JoinSet::new();
-        let client = reqwest::Client::builder()
-            // .proxy(reqwest::Proxy::all("socks5://127.0.0.1:9090").unwrap())
-            .redirect(reqwest::redirect::Policy::none())
-            .user_agent("https://uob-team-project-2022.nihaal.dev/")
-            .min_tls_version(reqwest::tls::Version::TLS_1_2)
-            .build()
-            .unwrap();
-        for team in TEAMS {
-            let client = client.clone();
+        for team in checker.roster() {
+            let team = team.clone();
+            let checker = checker.clone();
+            let client = checker.client().clone();
+            let journeys = checker
+                .journeys_dir()
+                .map_or(Ok(vec![]), |dir| load_journeys(dir, team.team_number));
             join_set.spawn(async move {
-                let (http_result, https_result) = tokio::join!(
-                    http_check_protocol(client.clone(), team, HttpProtocol::Http),
-                    http_check_protocol(client.clone(), team, HttpProtocol::Https)
+                let team = &team;
+                let endpoints = endpoint_results_attempt(&checker, team, attempt);
+                let health_checks = async {
+                    let mut health_check_results = vec![];
+                    for check in team.health_checks.iter() {
+                        health_check_results.push(HealthCheckResult {
+                            check: check.clone(),
+                            result: health_check(client.clone(), check).await,
+                        });
+                    }
+                    health_check_results
+                };
+                let journeys = async {
+                    match journeys {
+                        Ok(journeys) => {
+                            let mut journey_results = vec![];
+                            for journey in &journeys {
+                                journey_results
+                                    .push(run_journey(checker.client_builder(), journey).await);
+                            }
+                            journey_results
+                        }
+                        Err(invalid) => vec![invalid],
+                    }
+                };
+                let websockets = async {
+                    let mut websocket_results = vec![];
+                    for check in team.websockets.iter() {
+                        websocket_results.push(WebSocketCheckResult {
+                            check: check.clone(),
+                            result: websocket_check(&checker, check).await,
+                        });
+                    }
+                    websocket_results
+                };
+                let ports = async {
+                    match &team.audited_ports {
+                        Some(ports) => Some(
+                            ports_check(&checker, &team.primary_endpoint().domain, ports).await,
+                        ),
+                        None => None,
+                    }
+                };
+                // The checks that inspect the homepage share one request for it
+                let homepage_checks = async {
+                    let homepage = fetch_homepage(&client, team.primary_endpoint()).await;
+                    let homepage = homepage.as_ref();
+                    let (hygiene, assets, crawl, security) = tokio::join!(
+                        hygiene_check(client.clone(), homepage),
+                        assets_check(client.clone(), homepage),
+                        crawl_check(client.clone(), team.primary_endpoint(), homepage),
+                        security_check(client.clone(), team, homepage),
+                    );
+                    (
+                        capabilities_check(homepage),
+                        hygiene,
+                        assets,
+                        crawl,
+                        security,
+                        fingerprint_check(homepage),
+                    )
+                };
+                let (
+                    endpoints,
+                    health_checks,
+                    journeys,
+                    websockets,
+                    www_result,
+                    (
+                        capabilities_result,
+                        hygiene_result,
+                        assets_result,
+                        crawl_result,
+                        security_result,
+                        stack_result,
+                    ),
+                    exposure_result,
+                    ports_result,
+                ) = tokio::join!(
+                    endpoints,
+                    health_checks,
+                    journeys,
+                    websockets,
+                    www_check(client.clone(), team.primary_endpoint()),
+                    homepage_checks,
+                    exposure_check(client.clone(), team.primary_endpoint()),
+                    ports
                 );
                 TeamResult {
                     team: team.clone(),
-                    http: http_result.into(),
-                    https: https_result.into(),
+                    endpoints,
+                    health_checks,
+                    journeys,
+                    websockets,
+                    www: www_result,
+                    capabilities: capabilities_result,
+                    hygiene: hygiene_result,
+                    assets: assets_result,
+                    crawl: crawl_result,
+                    security: security_result,
+                    exposure: exposure_result,
+                    stack: stack_result,
+                    ports: ports_result,
                 }
             });
         }
         while let Some(handle) = join_set.join_next().await {
-            results.push(handle.unwrap());
+            results.push(handle.map_err(CheckError::Panicked)?);
         }
 
-        if results.iter().all(|result| {
-            matches!(
-                result.http,
-                ProtocolResult::Timeout | ProtocolResult::FailedConnect
-            ) && matches!(
-                result.https,
-                ProtocolResult::Timeout | ProtocolResult::FailedConnect
-            )
-        }) {
+        if results
+            .iter()
+            .flat_map(|result| &result.endpoints)
+            .all(|result| {
+                matches!(
+                    result.http,
+                    ProtocolResult::Timeout(_) | ProtocolResult::FailedConnect(_)
+                ) && matches!(
+                    result.https,
+                    ProtocolResult::Timeout(_) | ProtocolResult::FailedConnect(_)
+                )
+            })
+        {
             tokio::time::sleep(Duration::from_secs(1)).await;
             continue;
         }
 
-        return results;
+        return Ok(results);
     }
-    panic!("connection failed");
+    Err(CheckError::Unreachable)
 }
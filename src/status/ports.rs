@@ -0,0 +1,112 @@
+use super::Checker;
+use std::{net::SocketAddr, time::Duration};
+use tokio::{io::AsyncReadExt, net::TcpStream};
+
+/// How long to wait for a service to announce itself after connecting
+const BANNER_TIMEOUT_MS: u64 = 500;
+const MAX_BANNER_BYTES: usize = 256;
+
+pub struct OpenPort {
+    pub port: u16,
+    /// The service usually run on the port
+    pub service: Option<&'static str>,
+    /// The first line the service sent without being prompted, e.g. `SSH-2.0-OpenSSH_8.9p1`
+    pub banner: Option<String>,
+}
+
+impl OpenPort {
+    /// Databases and caches, which should never be reachable from the internet
+    #[must_use]
+    pub const fn is_datastore(&self) -> bool {
+        matches!(self.port, 1433 | 3306 | 5432 | 6379 | 9200 | 11211 | 27017)
+    }
+}
+
+pub enum PortsResult {
+    Audited {
+        address: String,
+        open: Vec<OpenPort>,
+    },
+    Unresolved,
+}
+
+const fn service_name(port: u16) -> Option<&'static str> {
+    Some(match port {
+        21 => "FTP",
+        22 => "SSH",
+        25 => "SMTP",
+        1433 => "SQL Server",
+        3000 | 5000 | 8000 | 8080 | 8081 | 8888 => "Development server",
+        3306 => "MySQL",
+        5432 => "PostgreSQL",
+        5601 => "Kibana",
+        6379 => "Redis",
+        8443 => "HTTPS alternate",
+        9000 => "Portainer/PHP-FPM",
+        9200 => "Elasticsearch",
+        11211 => "Memcached",
+        15672 => "RabbitMQ management",
+        27017 => "MongoDB",
+        _ => return None,
+    })
+}
+
+/// Only reads what the service sends unprompted, so nothing is ever sent to it
+async fn read_banner(stream: &mut TcpStream) -> Option<String> {
+    let mut buffer = [0; MAX_BANNER_BYTES];
+    let length = tokio::time::timeout(
+        Duration::from_millis(BANNER_TIMEOUT_MS),
+        stream.read(&mut buffer),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    // Binary greetings such as MySQL's still contain the version as text
+    let banner: String = buffer[..length]
+        .split(|byte| !byte.is_ascii_graphic() && *byte != b' ')
+        .filter(|run| run.len() >= 3)
+        .map(|run| String::from_utf8_lossy(run).into_owned())
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some(banner).filter(|banner| !banner.is_empty())
+}
+
+async fn probe_port(address: SocketAddr, timeout: Duration) -> Option<OpenPort> {
+    let mut stream = tokio::time::timeout(timeout, TcpStream::connect(address))
+        .await
+        .ok()?
+        .ok()?;
+    Some(OpenPort {
+        port: address.port(),
+        service: service_name(address.port()),
+        banner: read_banner(&mut stream).await,
+    })
+}
+
+/// Connects directly rather than through the checker's proxy, but honours its resolver and timeout
+pub(super) async fn ports_check(checker: &Checker, domain: &str, ports: &[u16]) -> PortsResult {
+    let host = domain.split_once(':').map_or(domain, |(host, _)| host);
+    let address = match checker.resolved(host) {
+        Some(address) => Some(address),
+        None => tokio::net::lookup_host((host, 0))
+            .await
+            .ok()
+            .and_then(|mut addresses| addresses.next()),
+    };
+    let Some(address) = address else {
+        return PortsResult::Unresolved;
+    };
+    let open = futures_util::future::join_all(
+        ports
+            .iter()
+            .map(|port| probe_port(SocketAddr::new(address.ip(), *port), checker.timeout())),
+    )
+    .await
+    .into_iter()
+    .flatten()
+    .collect();
+    PortsResult::Audited {
+        address: address.ip().to_string(),
+        open,
+    }
+}
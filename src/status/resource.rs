@@ -0,0 +1,119 @@
+use crate::teams::Endpoint;
+use std::io::Read;
+
+/// The encodings [`decompress`] can decode, so a server that honours the header never sends one it
+/// can't, e.g. zstd
+const ACCEPT_ENCODING: &str = "gzip, deflate, br";
+
+/// A page or asset as a browser would fetch it, asking for it compressed
+pub(super) struct Resource {
+    pub(super) url: reqwest::Url,
+    pub(super) status: reqwest::StatusCode,
+    pub(super) version: reqwest::Version,
+    pub(super) headers: reqwest::header::HeaderMap,
+    /// The size of the body as transferred, before it's decompressed
+    pub(super) transferred_bytes: usize,
+    /// `None` if the body couldn't be decompressed
+    pub(super) body: Option<Vec<u8>>,
+}
+
+/// `None` for an encoding that isn't in [`ACCEPT_ENCODING`], or a body that doesn't decode
+fn decompress(content_encoding: Option<&str>, body: Vec<u8>) -> Option<Vec<u8>> {
+    let mut decompressed = vec![];
+    match content_encoding {
+        None => return Some(body),
+        Some("gzip") => flate2::read::GzDecoder::new(body.as_slice())
+            .read_to_end(&mut decompressed)
+            .ok()?,
+        Some("deflate") => flate2::read::ZlibDecoder::new(body.as_slice())
+            .read_to_end(&mut decompressed)
+            .ok()?,
+        Some("br") => brotli::Decompressor::new(body.as_slice(), 4096)
+            .read_to_end(&mut decompressed)
+            .ok()?,
+        Some(_) => return None,
+    };
+    Some(decompressed)
+}
+
+impl Resource {
+    pub(super) async fn fetch(client: &reqwest::Client, url: &str) -> Result<Self, reqwest::Error> {
+        let response = client
+            .get(url)
+            .header("Accept-Encoding", ACCEPT_ENCODING)
+            .send()
+            .await?;
+        let url = response.url().clone();
+        let status = response.status();
+        let version = response.version();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?.to_vec();
+        let mut resource = Self {
+            url,
+            status,
+            version,
+            headers,
+            transferred_bytes: body.len(),
+            body: None,
+        };
+        resource.body = decompress(resource.content_encoding(), body);
+        Ok(resource)
+    }
+
+    pub(super) fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+
+    pub(super) fn content_encoding(&self) -> Option<&str> {
+        self.header("Content-Encoding")
+            .filter(|content_encoding| *content_encoding != "identity")
+    }
+
+    /// The decompressed body, or nothing if it couldn't be decompressed
+    pub(super) fn text(&self) -> String {
+        String::from_utf8_lossy(self.body.as_deref().unwrap_or_default()).into_owned()
+    }
+}
+
+/// Fetched once per team and shared by the checks that inspect the homepage. `None` if it couldn't
+/// be fetched
+pub(super) async fn fetch_homepage(
+    client: &reqwest::Client,
+    endpoint: &Endpoint,
+) -> Option<Resource> {
+    let url = format!("https://{}{}", endpoint.domain, endpoint.path);
+    Resource::fetch(client, &url).await.ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn decompresses_every_accepted_encoding() {
+        let body = b"<!DOCTYPE html><title>Team</title>".repeat(10);
+        let mut gzip = flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+        gzip.write_all(&body).unwrap();
+        let mut deflate = flate2::write::ZlibEncoder::new(vec![], flate2::Compression::default());
+        deflate.write_all(&body).unwrap();
+        let mut br = vec![];
+        brotli::CompressorWriter::new(&mut br, 4096, 5, 22)
+            .write_all(&body)
+            .unwrap();
+        for (encoding, compressed) in [
+            ("gzip", gzip.finish().unwrap()),
+            ("deflate", deflate.finish().unwrap()),
+            ("br", br),
+        ] {
+            assert!(ACCEPT_ENCODING.contains(encoding));
+            assert_eq!(
+                decompress(Some(encoding), compressed).as_deref(),
+                Some(&body[..]),
+                "{}",
+                encoding
+            );
+        }
+        assert_eq!(decompress(Some("zstd"), body.clone()), None);
+    }
+}
@@ -0,0 +1,199 @@
+use super::http::set_cookies;
+use super::resource::Resource;
+use crate::teams::Team;
+use std::sync::OnceLock;
+
+/// Paths commonly served straight from a directory, where autoindex is often left on
+const LISTING_PATHS: &[&str] = &["/static/", "/uploads/", "/media/", "/assets/", "/images/"];
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+impl Severity {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "Low",
+            Self::Medium => "Medium",
+            Self::High => "High",
+        }
+    }
+}
+
+pub enum SecurityFinding {
+    /// A cookie set over HTTPS without some of `Secure`, `HttpOnly` and `SameSite`
+    InsecureCookie {
+        url: String,
+        name: String,
+        missing: Vec<&'static str>,
+        session: bool,
+    },
+    /// A session cookie set on a plain HTTP response, where it can be read in transit
+    SessionCookieOverHttp { url: String, name: String },
+    /// A `Server` or `X-Powered-By` header including a version number, e.g. `nginx/1.18.0 (Ubuntu)`
+    VersionDisclosure {
+        url: String,
+        header: &'static str,
+        value: String,
+    },
+    /// An automatically generated index of a directory's files
+    DirectoryListing { url: String },
+}
+
+impl SecurityFinding {
+    #[must_use]
+    pub const fn severity(&self) -> Severity {
+        match self {
+            Self::InsecureCookie { session: true, .. } => Severity::Medium,
+            Self::SessionCookieOverHttp { .. } => Severity::High,
+            Self::InsecureCookie { session: false, .. }
+            | Self::VersionDisclosure { .. }
+            | Self::DirectoryListing { .. } => Severity::Low,
+        }
+    }
+}
+
+pub enum SecurityResult {
+    Inspected { findings: Vec<SecurityFinding> },
+    Unavailable,
+}
+
+/// Whether a header names a product with its version, e.g. `nginx/1.18.0` or `PHP/8.1.2`, rather
+/// than just the product, e.g. `AmazonS3` or `h2o`
+fn discloses_version(value: &str) -> bool {
+    static VERSIONED_PRODUCT: OnceLock<regex::Regex> = OnceLock::new();
+    VERSIONED_PRODUCT
+        .get_or_init(|| regex::Regex::new(r"[A-Za-z][\w.+-]*/v?\d").unwrap())
+        .is_match(value)
+}
+
+fn response_findings(
+    url: &str,
+    headers: &reqwest::header::HeaderMap,
+    https: bool,
+    check_server_headers: bool,
+) -> Vec<SecurityFinding> {
+    let mut findings = vec![];
+    if check_server_headers {
+        for header in ["Server", "X-Powered-By"] {
+            if let Some(value) = headers
+                .get(header)
+                .and_then(|value| value.to_str().ok())
+                .filter(|value| discloses_version(value))
+            {
+                findings.push(SecurityFinding::VersionDisclosure {
+                    url: url.to_owned(),
+                    header,
+                    value: value.to_owned(),
+                });
+            }
+        }
+    }
+    for cookie in set_cookies(headers) {
+        if !https {
+            if cookie.is_session() {
+                findings.push(SecurityFinding::SessionCookieOverHttp {
+                    url: url.to_owned(),
+                    name: cookie.name,
+                });
+            }
+            continue;
+        }
+        let missing: Vec<&'static str> = [
+            (cookie.secure, "Secure"),
+            (cookie.http_only, "HttpOnly"),
+            (cookie.same_site, "SameSite"),
+        ]
+        .into_iter()
+        .filter(|(present, _)| !present)
+        .map(|(_, attribute)| attribute)
+        .collect();
+        if !missing.is_empty() {
+            findings.push(SecurityFinding::InsecureCookie {
+                url: url.to_owned(),
+                session: cookie.is_session(),
+                name: cookie.name,
+                missing,
+            });
+        }
+    }
+    findings
+}
+
+/// `None` if the page couldn't be fetched
+async fn fetched_findings(
+    client: &reqwest::Client,
+    url: &str,
+    https: bool,
+    check_server_headers: bool,
+) -> Option<Vec<SecurityFinding>> {
+    let response = client.get(url).send().await.ok()?;
+    Some(response_findings(
+        url,
+        response.headers(),
+        https,
+        check_server_headers,
+    ))
+}
+
+async fn is_directory_listing(client: &reqwest::Client, url: &str) -> bool {
+    let Ok(response) = client.get(url).send().await else {
+        return false;
+    };
+    if !response.status().is_success() {
+        return false;
+    }
+    let body = response.text().await.unwrap_or_default();
+    // nginx and Apache, then Python's http.server and Express's serve-index
+    body.contains("<title>Index of /")
+        || body.contains("<title>Directory listing for /")
+        || body.contains("<title>listing directory /")
+}
+
+/// Inspects the headers of the homepage and login page over both HTTP and HTTPS, and looks for
+/// directory listings
+pub(super) async fn security_check(
+    client: reqwest::Client,
+    team: &Team,
+    homepage: Option<&Resource>,
+) -> SecurityResult {
+    // The login page is optional, but the homepage isn't
+    let Some(homepage) = homepage else {
+        return SecurityResult::Unavailable;
+    };
+    let domain = &team.primary_endpoint().domain;
+    let mut findings = vec![];
+    for (index, path) in [
+        Some(&team.primary_endpoint().path),
+        team.login_path.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .enumerate()
+    {
+        let url = format!("https://{}{}", domain, path);
+        // The same server usually serves both pages, so its headers are only reported once
+        let https_findings = if index == 0 {
+            Some(response_findings(&url, &homepage.headers, true, true))
+        } else {
+            fetched_findings(&client, &url, true, false).await
+        };
+        findings.extend(https_findings.into_iter().flatten());
+        if let Some(http_findings) =
+            fetched_findings(&client, &format!("http://{}{}", domain, path), false, false).await
+        {
+            findings.extend(http_findings);
+        }
+    }
+    for path in LISTING_PATHS {
+        let url = format!("https://{}{}", domain, path);
+        if is_directory_listing(&client, &url).await {
+            findings.push(SecurityFinding::DirectoryListing { url });
+        }
+    }
+    SecurityResult::Inspected { findings }
+}
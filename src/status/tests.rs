@@ -0,0 +1,425 @@
+//! Checks a roster of teams impersonated by local servers, which are reached through a custom
+//! resolver. Each server speaks both HTTP and HTTPS on one port, as the port is part of the domain
+//! that both protocols are checked on.
+
+use super::journey::{load_journeys, run_journey};
+use super::ports::ports_check;
+use super::{
+    endpoint_results, BuildError, Checker, EndpointResult, PortsResult, ProtocolResult, StepOutcome,
+};
+use crate::teams::{Endpoint, Expected, Team};
+use rcgen::{BasicConstraints, Certificate, CertificateParams, DnType, IsCa};
+use std::{net::SocketAddr, sync::Arc};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpListener,
+};
+use tokio_rustls::{rustls, TlsAcceptor};
+
+const NGINX_DEFAULT_PAGE: &str = "<!DOCTYPE html>
+<html>
+<head>
+<title>Welcome to nginx!</title>
+</head>
+<body>
+<h1>Welcome to nginx!</h1>
+<p>If you see this page, the nginx web server is successfully installed and
+working. Further configuration is required.</p>
+</body>
+</html>
+";
+
+/// The first byte of a TLS handshake
+const TLS_HANDSHAKE: u8 = 0x16;
+
+enum CertificateKind {
+    /// Signed by the test CA, which the client trusts
+    Valid,
+    SelfSigned,
+    Expired,
+    /// Signed by the test CA, but for another host
+    WrongHost,
+}
+
+fn test_ca() -> Certificate {
+    let mut params = CertificateParams::default();
+    params
+        .distinguished_name
+        .push(DnType::CommonName, "Test CA");
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    Certificate::from_params(params).unwrap()
+}
+
+fn tls_acceptor(host: &str, kind: &CertificateKind, ca: &Certificate) -> TlsAcceptor {
+    let name = match kind {
+        CertificateKind::WrongHost => "other.test",
+        _ => host,
+    };
+    let mut params = CertificateParams::new(vec![name.to_owned()]);
+    params.distinguished_name.push(DnType::CommonName, name);
+    if matches!(kind, CertificateKind::Expired) {
+        params.not_before = rcgen::date_time_ymd(2020, 1, 1);
+        params.not_after = rcgen::date_time_ymd(2021, 1, 1);
+    }
+    let certificate = Certificate::from_params(params).unwrap();
+    let der = match kind {
+        CertificateKind::SelfSigned => certificate.serialize_der(),
+        _ => certificate.serialize_der_with_signer(ca),
+    }
+    .unwrap();
+    let config = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(
+            vec![rustls::Certificate(der)],
+            rustls::PrivateKey(certificate.serialize_private_key_der()),
+        )
+        .unwrap();
+    TlsAcceptor::from(Arc::new(config))
+}
+
+/// Responds like a team's server would, depending on the path
+async fn respond(mut stream: impl AsyncRead + AsyncWrite + Unpin, tls: bool) {
+    let mut request = vec![];
+    let mut buffer = [0; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        match stream.read(&mut buffer).await {
+            Ok(0) | Err(_) => return,
+            Ok(read) => request.extend_from_slice(&buffer[..read]),
+        }
+    }
+    let request = String::from_utf8_lossy(&request);
+    let path = request.split_whitespace().nth(1).unwrap_or("/");
+    let host = request
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("Host").then(|| value.trim())
+        })
+        .unwrap_or_default();
+    if path == "/garbled" {
+        // Not HTTP, so the request fails without a timeout or connection error
+        let _ = stream.write_all(b"garbled\r\n\r\n").await;
+        let _ = stream.shutdown().await;
+        return;
+    }
+    let (status, location, body) = match (path, tls) {
+        // Redirects to the homepage, which is only correct for the homepage and moved endpoints
+        ("/", false) | ("/moved", _) => (
+            "301 Moved Permanently",
+            Some(format!("https://{}/", host)),
+            "",
+        ),
+        ("/", true) => ("200 OK", None, "<!DOCTYPE html><title>Team</title>"),
+        ("/nginx", _) => ("200 OK", None, NGINX_DEFAULT_PAGE),
+        ("/mixed", true) => (
+            "200 OK",
+            None,
+            r#"<!DOCTYPE html><script src="http://cdn.test/app.js"></script>"#,
+        ),
+        ("/teapot", _) => ("418 I'm a teapot", None, ""),
+        ("/form", true) => (
+            "200 OK",
+            None,
+            r#"<form><input name="csrf" value="abc123"></form>"#,
+        ),
+        ("/echo?token=abc123", true) => ("200 OK", None, r#"{"token": "abc123"}"#),
+        // e.g. to a login form, which is fine over HTTPS
+        ("/account", true) => ("302 Found", Some("/login".to_owned()), ""),
+        ("/slow", _) => return std::future::pending().await,
+        _ => ("404 Not Found", None, ""),
+    };
+    let location = location
+        .map(|location| format!("Location: {}\r\n", location))
+        .unwrap_or_default();
+    let response = format!(
+        "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        location,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// Serves HTTP and HTTPS on the same port, telling them apart by the first byte
+async fn mock_server(acceptor: TlsAcceptor) -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                let mut first = [0];
+                if stream.peek(&mut first).await.is_err() {
+                    return;
+                }
+                if first[0] == TLS_HANDSHAKE {
+                    if let Ok(stream) = acceptor.accept(stream).await {
+                        respond(stream, true).await;
+                    }
+                } else {
+                    respond(stream, false).await;
+                }
+            });
+        }
+    });
+    port
+}
+
+/// A port nothing is listening on
+async fn closed_port() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    listener.local_addr().unwrap().port()
+}
+
+fn endpoint(domain: String, path: &'static str) -> Endpoint {
+    Endpoint {
+        name: format!("{}{}", domain, path).into(),
+        domain: domain.into(),
+        path: path.into(),
+        expected: Expected::Serve,
+    }
+}
+
+/// An endpoint that has moved to the homepage
+fn moved_endpoint(domain: String) -> Endpoint {
+    Endpoint {
+        expected: Expected::Redirect {
+            to: format!("https://{}/", domain).into(),
+        },
+        ..endpoint(domain, "/moved")
+    }
+}
+
+/// Checks a team with an endpoint for each of `endpoints`, in order
+async fn check(endpoints: Vec<Endpoint>, checker: &Checker) -> Vec<EndpointResult> {
+    let team = Team {
+        team_number: 1,
+        endpoints: endpoints.into(),
+        health_checks: vec![].into(),
+        websockets: vec![].into(),
+        login_path: None,
+        audited_ports: None,
+    };
+    endpoint_results(checker, &team).await
+}
+
+/// Starts a server for each kind of certificate, and a checker that trusts the test CA and
+/// resolves every host to the servers
+async fn mock_teams() -> (Checker, [String; 4], String) {
+    let ca = test_ca();
+    let mut domains = vec![];
+    let mut builder = Checker::builder().add_root_certificate(
+        reqwest::Certificate::from_der(&ca.serialize_der().unwrap()).unwrap(),
+    );
+    for (host, kind) in [
+        ("team.test", CertificateKind::Valid),
+        ("self-signed.test", CertificateKind::SelfSigned),
+        ("expired.test", CertificateKind::Expired),
+        ("wrong-host.test", CertificateKind::WrongHost),
+    ] {
+        let port = mock_server(tls_acceptor(host, &kind, &ca)).await;
+        domains.push(format!("{}:{}", host, port));
+        // The port is ignored, and taken from the URL instead
+        builder = builder.resolve(host, SocketAddr::from(([127, 0, 0, 1], 0)));
+    }
+    let closed = format!("closed.test:{}", closed_port().await);
+    builder = builder.resolve("closed.test", SocketAddr::from(([127, 0, 0, 1], 0)));
+    // Re-records `fixtures/http`, as described in `status::fixtures`
+    if let Some(dir) = std::env::var_os("RECORD_FIXTURES") {
+        builder = builder.record_fixtures(dir);
+    }
+    (
+        builder.build().unwrap(),
+        domains.try_into().unwrap(),
+        closed,
+    )
+}
+
+#[tokio::test]
+async fn classifies_each_kind_of_response() {
+    let (checker, [team, ..], _) = mock_teams().await;
+    let results = check(
+        [
+            "/", "/nginx", "/moved", "/mixed", "/teapot", "/missing", "/slow", "/account",
+            "/garbled",
+        ]
+        .into_iter()
+        .map(|path| endpoint(team.clone(), path))
+        .chain([moved_endpoint(team.clone())])
+        .collect(),
+        &checker,
+    )
+    .await;
+    let results: Vec<_> = results
+        .iter()
+        .map(|result| (&result.http, &result.https))
+        .collect();
+
+    assert!(matches!(
+        results[0],
+        (
+            ProtocolResult::CorrectRedirect(301),
+            ProtocolResult::Ok(200)
+        )
+    ));
+    assert!(matches!(
+        results[1],
+        (
+            ProtocolResult::NginxDefaultPage(200),
+            ProtocolResult::NginxDefaultPage(200)
+        )
+    ));
+    assert!(matches!(
+        results[2],
+        (ProtocolResult::IncorrectRedirect(301), _)
+    ));
+    assert!(
+        matches!(results[3], (_, ProtocolResult::MixedContent(200, insecure_urls)) if insecure_urls == &["http://cdn.test/app.js"])
+    );
+    assert!(matches!(
+        results[4],
+        (
+            ProtocolResult::UnexpectedResponse(418),
+            ProtocolResult::UnexpectedResponse(418)
+        )
+    ));
+    assert!(matches!(
+        results[5],
+        (
+            ProtocolResult::UnexpectedResponse(404),
+            ProtocolResult::UnexpectedResponse(404)
+        )
+    ));
+    assert!(matches!(
+        results[6],
+        (ProtocolResult::Timeout(_), ProtocolResult::Timeout(_))
+    ));
+    assert!(matches!(results[7], (_, ProtocolResult::Ok(302))));
+    assert!(matches!(
+        results[8],
+        (ProtocolResult::Error(_), ProtocolResult::Error(_))
+    ));
+    assert!(matches!(
+        results[9],
+        (
+            ProtocolResult::CorrectRedirect(301),
+            ProtocolResult::CorrectRedirect(301)
+        )
+    ));
+}
+
+#[tokio::test]
+async fn classifies_each_kind_of_certificate() {
+    let (checker, [team, self_signed, expired, wrong_host], closed) = mock_teams().await;
+    let results = check(
+        [team, self_signed, expired, wrong_host, closed]
+            .into_iter()
+            .map(|domain| endpoint(domain, "/"))
+            .collect(),
+        &checker,
+    )
+    .await;
+    let https: Vec<_> = results.iter().map(|result| &result.https).collect();
+
+    assert!(matches!(https[0], ProtocolResult::Ok(200)));
+    assert!(matches!(https[1], ProtocolResult::UntrustedCertificate(_)));
+    assert!(matches!(https[2], ProtocolResult::UntrustedCertificate(_)));
+    assert!(matches!(https[3], ProtocolResult::InvalidCertificate(_)));
+    assert!(matches!(https[4], ProtocolResult::FailedConnect(_)));
+    assert!(matches!(
+        results[4].http,
+        ProtocolResult::FailedConnect(ref diagnostic) if diagnostic.attempt == 1
+    ));
+}
+
+#[tokio::test]
+async fn audits_ports_where_the_checker_resolves_to() {
+    let (checker, [team, ..], closed) = mock_teams().await;
+    let port = |domain: &str| domain.rsplit_once(':').unwrap().1.parse().unwrap();
+    let result = ports_check(&checker, &team, &[port(&team), port(&closed)]).await;
+
+    let PortsResult::Audited { address, open } = result else {
+        panic!("team.test wasn't resolved");
+    };
+    assert_eq!(address, "127.0.0.1");
+    assert_eq!(
+        open.iter().map(|open| open.port).collect::<Vec<_>>(),
+        [port(&team)]
+    );
+}
+
+#[tokio::test]
+async fn runs_journeys_until_a_step_fails() {
+    let (checker, [team, ..], _) = mock_teams().await;
+    let dir = std::env::temp_dir().join(format!("journeys-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("team-01.yaml"),
+        format!(
+            r#"
+- name: Sign in
+  steps:
+    - name: Form
+      url: https://{team}/form
+      capture:
+        csrf:
+          regex: 'name="csrf" value="([^"]+)"'
+    - name: Echo
+      url: https://{team}/echo?token=${{csrf}}
+      expect:
+        json: ['$.token == "abc123"']
+    - name: Missing
+      url: https://{team}/missing
+    - name: Never run
+      url: https://{team}/
+"#
+        ),
+    )
+    .unwrap();
+    let journeys = load_journeys(&dir, 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+    let journeys = journeys.ok().expect("the journey file is invalid");
+    assert_eq!(journeys.len(), 1);
+    let result = run_journey(checker.client_builder(), &journeys[0]).await;
+
+    let steps: Vec<_> = result
+        .steps
+        .iter()
+        .map(|step| (step.url.as_str(), &step.outcome))
+        .collect();
+    assert_eq!(steps.len(), 3);
+    assert!(matches!(
+        steps[0].1,
+        StepOutcome::Passed { status_code: 200 }
+    ));
+    // The captured token isn't shown
+    assert_eq!(steps[1].0, format!("https://{}/echo?token=${{csrf}}", team));
+    assert!(matches!(
+        steps[1].1,
+        StepOutcome::Passed { status_code: 200 }
+    ));
+    assert!(matches!(
+        steps[2].1,
+        StepOutcome::UnexpectedResponse { status_code: 404 }
+    ));
+}
+
+#[test]
+fn rejects_teams_without_endpoints() {
+    let roster = vec![Team {
+        team_number: 1,
+        endpoints: vec![].into(),
+        health_checks: vec![].into(),
+        websockets: vec![].into(),
+        login_path: None,
+        audited_ports: None,
+    }];
+    assert!(matches!(
+        Checker::builder().roster(roster).build(),
+        Err(BuildError::NoEndpoints { team_number: 1 })
+    ));
+}
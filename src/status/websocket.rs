@@ -0,0 +1,114 @@
+use super::checker::USER_AGENT;
+use super::http::{certificate_error, RequestError};
+use super::Checker;
+use crate::teams::{WebSocketCheck, WebSocketProbe};
+use futures_util::{SinkExt, StreamExt};
+use std::net::SocketAddr;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::{
+    self,
+    client::IntoClientRequest,
+    handshake::client::{Request, Response},
+    http::HeaderValue,
+    Message,
+};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+pub enum WebSocketResult {
+    Connected,
+    Replied,
+    NoReply,
+    Closed,
+    HandshakeRejected { status_code: u16 },
+    Timeout,
+    UntrustedCertificate,
+    InvalidCertificate,
+    FailedConnect,
+    Error,
+}
+
+fn error_result(e: &tungstenite::Error) -> WebSocketResult {
+    match e {
+        tungstenite::Error::Http(response) => WebSocketResult::HandshakeRejected {
+            status_code: response.status().as_u16(),
+        },
+        tungstenite::Error::Io(e) => match certificate_error(&format!("{}", e)) {
+            Some(RequestError::InvalidCertificate) => WebSocketResult::InvalidCertificate,
+            Some(_) => WebSocketResult::UntrustedCertificate,
+            None => WebSocketResult::FailedConnect,
+        },
+        tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed => {
+            WebSocketResult::Closed
+        }
+        _ => WebSocketResult::Error,
+    }
+}
+
+/// As `connect_async`, but to the address the checker resolves the host to, if it does
+async fn connect(
+    checker: &Checker,
+    request: Request,
+) -> tungstenite::Result<(WebSocketStream<MaybeTlsStream<TcpStream>>, Response)> {
+    let Some(address) = request.uri().host().and_then(|host| checker.resolved(host)) else {
+        return tokio_tungstenite::connect_async(request).await;
+    };
+    let port = request
+        .uri()
+        .port_u16()
+        .unwrap_or(match request.uri().scheme_str() {
+            Some("wss") => 443,
+            _ => 80,
+        });
+    let stream = TcpStream::connect(SocketAddr::new(address.ip(), port))
+        .await
+        .map_err(tungstenite::Error::Io)?;
+    tokio_tungstenite::client_async_tls(request, stream).await
+}
+
+/// Connects directly rather than through the checker's proxy, but honours its resolver and timeout
+pub(super) async fn websocket_check(checker: &Checker, check: &WebSocketCheck) -> WebSocketResult {
+    let timeout = checker.timeout();
+    let Ok(mut request) = check.url.into_client_request() else {
+        return WebSocketResult::Error;
+    };
+    request
+        .headers_mut()
+        .insert("User-Agent", HeaderValue::from_static(USER_AGENT));
+
+    let mut stream = match tokio::time::timeout(timeout, connect(checker, request)).await {
+        Err(_) => return WebSocketResult::Timeout,
+        Ok(Err(e)) => return error_result(&e),
+        Ok(Ok((stream, _))) => stream,
+    };
+
+    let Some(probe) = &check.probe else {
+        return WebSocketResult::Connected;
+    };
+    let message = match probe {
+        WebSocketProbe::Ping => Message::Ping(b"uob-team-project".to_vec()),
+        WebSocketProbe::Message { text } => Message::Text(text.to_string()),
+    };
+    if let Err(e) = stream.send(message).await {
+        return error_result(&e);
+    }
+
+    let reply = async {
+        while let Some(message) = stream.next().await {
+            match (probe, message) {
+                (_, Err(e)) => return error_result(&e),
+                (_, Ok(Message::Close(_))) => return WebSocketResult::Closed,
+                (WebSocketProbe::Ping, Ok(Message::Pong(_)))
+                | (WebSocketProbe::Message { .. }, Ok(Message::Text(_) | Message::Binary(_))) => {
+                    return WebSocketResult::Replied
+                }
+                _ => {}
+            }
+        }
+        WebSocketResult::Closed
+    };
+    let result = tokio::time::timeout(timeout, reply)
+        .await
+        .unwrap_or(WebSocketResult::NoReply);
+    let _ = stream.close(None).await;
+    result
+}
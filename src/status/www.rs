@@ -0,0 +1,108 @@
+use super::http::{classify_error, HttpProtocol, RequestError};
+use crate::teams::Endpoint;
+
+pub enum WwwResult {
+    Consistent { canonical: String },
+    BothServe,
+    NoCanonicalHost,
+    IncorrectRedirect { host: String },
+    UnexpectedResponse { host: String, status_code: u16 },
+    Timeout { host: String },
+    UntrustedCertificate { host: String },
+    InvalidCertificate { host: String },
+    FailedConnect { host: String },
+    Error { host: String },
+}
+
+enum HostResponse {
+    Serves,
+    Redirects(String),
+    Unexpected(u16),
+}
+
+async fn www_check_host(client: reqwest::Client, host: &str) -> Result<HostResponse, WwwResult> {
+    let url = format!("https://{}/", host);
+    match client.get(&url).send().await {
+        Ok(response) => {
+            let location = response
+                .headers()
+                .get("Location")
+                .and_then(|location| location.to_str().ok())
+                .map(ToOwned::to_owned);
+            match (response.status(), location) {
+                (reqwest::StatusCode::OK, _) => Ok(HostResponse::Serves),
+                // Local redirect
+                (reqwest::StatusCode::FOUND, Some(location)) if location.starts_with('/') => {
+                    Ok(HostResponse::Serves)
+                }
+                (
+                    reqwest::StatusCode::MOVED_PERMANENTLY
+                    | reqwest::StatusCode::FOUND
+                    | reqwest::StatusCode::TEMPORARY_REDIRECT
+                    | reqwest::StatusCode::PERMANENT_REDIRECT,
+                    Some(location),
+                ) => Ok(HostResponse::Redirects(location)),
+                (status_code, _) => Ok(HostResponse::Unexpected(status_code.as_u16())),
+            }
+        }
+        Err(e) => {
+            let host = host.to_owned();
+            Err(match classify_error(&e, HttpProtocol::Https) {
+                RequestError::Timeout => WwwResult::Timeout { host },
+                RequestError::UntrustedCertificate => WwwResult::UntrustedCertificate { host },
+                RequestError::InvalidCertificate => WwwResult::InvalidCertificate { host },
+                RequestError::FailedConnect => WwwResult::FailedConnect { host },
+                RequestError::Other => WwwResult::Error { host },
+            })
+        }
+    }
+}
+
+/// Checks that exactly one of the endpoint's domain and its `www.` (or apex) counterpart serves
+/// content, and that the other redirects to it over HTTPS.
+pub(super) async fn www_check(client: reqwest::Client, endpoint: &Endpoint) -> WwwResult {
+    let alternate_domain = endpoint.alternate_domain();
+    let (configured, alternate) = tokio::join!(
+        www_check_host(client.clone(), &endpoint.domain),
+        www_check_host(client, &alternate_domain)
+    );
+    let (configured, alternate) = match (configured, alternate) {
+        (Ok(configured), Ok(alternate)) => (configured, alternate),
+        (Err(result), _) | (_, Err(result)) => return result,
+    };
+
+    match (configured, alternate) {
+        (HostResponse::Unexpected(status_code), _) => WwwResult::UnexpectedResponse {
+            host: endpoint.domain.to_string(),
+            status_code,
+        },
+        (_, HostResponse::Unexpected(status_code)) => WwwResult::UnexpectedResponse {
+            host: alternate_domain,
+            status_code,
+        },
+        (HostResponse::Serves, HostResponse::Serves) => WwwResult::BothServe,
+        (HostResponse::Redirects(_), HostResponse::Redirects(_)) => WwwResult::NoCanonicalHost,
+        (HostResponse::Serves, HostResponse::Redirects(location)) => {
+            if location.starts_with(&format!("https://{}/", endpoint.domain)) {
+                WwwResult::Consistent {
+                    canonical: endpoint.domain.to_string(),
+                }
+            } else {
+                WwwResult::IncorrectRedirect {
+                    host: alternate_domain,
+                }
+            }
+        }
+        (HostResponse::Redirects(location), HostResponse::Serves) => {
+            if location.starts_with(&format!("https://{}/", alternate_domain)) {
+                WwwResult::Consistent {
+                    canonical: alternate_domain,
+                }
+            } else {
+                WwwResult::IncorrectRedirect {
+                    host: endpoint.domain.to_string(),
+                }
+            }
+        }
+    }
+}
@@ -1,242 +1,403 @@
 use serde::Serialize;
+use std::borrow::Cow;
+
+/// Text that's borrowed when compiled in, as in [`TEAMS`], and owned when loaded at runtime
+pub type Text = Cow<'static, str>;
+
+/// A list that's borrowed when compiled in, as in [`TEAMS`], and owned when loaded at runtime
+pub type List<T> = Cow<'static, [T]>;
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum Expected {
+    /// Serves content over HTTPS, with HTTP redirecting to HTTPS
+    Serve,
+    /// Redirects to `to` over both HTTP and HTTPS, e.g. a domain the team has moved away from
+    Redirect { to: Text },
+}
+
+#[derive(Clone, Serialize)]
+pub struct Endpoint {
+    pub name: Text,
+    pub domain: Text,
+    pub path: Text,
+    pub expected: Expected,
+}
+
+impl Endpoint {
+    #[must_use]
+    pub const fn website(domain: &'static str) -> Self {
+        Self {
+            name: Cow::Borrowed("Website"),
+            domain: Cow::Borrowed(domain),
+            path: Cow::Borrowed("/"),
+            expected: Expected::Serve,
+        }
+    }
+
+    /// The `www.` counterpart of the endpoint's domain, or the apex if the domain is already `www.`
+    #[must_use]
+    pub fn alternate_domain(&self) -> String {
+        match self.domain.strip_prefix("www.") {
+            Some(apex) => apex.to_owned(),
+            None => format!("www.{}", self.domain),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct HealthCheck {
+    pub name: Text,
+    pub url: Text,
+    pub status_code: u16,
+    /// JSONPath-style assertions on the response body, e.g. `$.status == "ok"`, `$.db.up != false`
+    /// or just `$.version` to assert that a value exists
+    pub assertions: List<Text>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum WebSocketProbe {
+    /// Sends a ping frame and expects a pong
+    Ping,
+    /// Sends a text message and expects any message back
+    Message { text: Text },
+}
+
+#[derive(Clone, Serialize)]
+pub struct WebSocketCheck {
+    pub name: Text,
+    pub url: Text,
+    pub probe: Option<WebSocketProbe>,
+}
+
+/// SSH, databases, caches and the usual development server and admin tool ports
+pub const COMMON_PORTS: &[u16] = &[
+    21, 22, 25, 1433, 3000, 3306, 5000, 5432, 5601, 6379, 8000, 8080, 8081, 8443, 8888, 9000, 9200,
+    11211, 15672, 27017,
+];
 
 #[derive(Clone, Serialize)]
 pub struct Team {
     pub team_number: u8,
-    pub domain: &'static str,
+    /// The first endpoint is the team's main site
+    pub endpoints: List<Endpoint>,
+    pub health_checks: List<HealthCheck>,
+    pub websockets: List<WebSocketCheck>,
+    /// Path on the primary endpoint's domain whose cookies are audited alongside the homepage's
+    pub login_path: Option<Text>,
+    /// Ports to check are closed on the team's server, if they've opted in to the audit
+    pub audited_ports: Option<List<u16>>,
+}
+
+impl Team {
+    const DEFAULT: Self = Self {
+        team_number: 0,
+        endpoints: Cow::Borrowed(&[]),
+        health_checks: Cow::Borrowed(&[]),
+        websockets: Cow::Borrowed(&[]),
+        login_path: None,
+        audited_ports: None,
+    };
+
+    /// # Panics
+    ///
+    /// If the team has no endpoints, which [`crate::status::CheckerBuilder::build`] rejects
+    #[must_use]
+    pub fn primary_endpoint(&self) -> &Endpoint {
+        &self.endpoints[0]
+    }
 }
 
 pub const TEAMS: &[Team] = &[
     Team {
         team_number: 1,
-        domain: "teamda01-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("teamda01-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 2,
-        domain: "teamda02-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("teamda02-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 3,
-        domain: "teamda03-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("teamda03-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 4,
-        domain: "greenertogether.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("greenertogether.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 5,
-        domain: "mypwcv.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("mypwcv.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 6,
-        domain: "teamd06-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("teamd06-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 7,
-        domain: "clubping.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("clubping.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 8,
-        domain: "teamd08-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("teamd08-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 9,
-        domain: "teamd09-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("teamd09-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 10,
-        domain: "housekeeping.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("housekeeping.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 11,
-        domain: "team11-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team11-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 12,
-        domain: "team12-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team12-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 13,
-        domain: "tellmyteacher.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("tellmyteacher.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 14,
-        domain: "uobsocieties.social",
+        endpoints: Cow::Borrowed(&[Endpoint::website("uobsocieties.social")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 15,
-        domain: "control.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("control.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 16,
-        domain: "anthology.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("anthology.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 17,
-        domain: "team17-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team17-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 18,
-        domain: "scran.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("scran.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 19,
-        domain: "nodenas.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("nodenas.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 20,
-        domain: "team20-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team20-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 21,
-        domain: "aurora.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("aurora.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 22,
-        domain: "team22-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team22-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 23,
-        domain: "team23-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team23-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 24,
-        domain: "travelmate.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("travelmate.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 25,
-        domain: "team25-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team25-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 26,
-        domain: "team26-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team26-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 27,
-        domain: "freebees.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("freebees.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 28,
-        domain: "orderlyapp.live",
+        endpoints: Cow::Borrowed(&[Endpoint::website("orderlyapp.live")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 29,
-        domain: "portfoliu.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("portfoliu.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 30,
-        domain: "team30-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team30-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 31,
-        domain: "team31-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team31-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 32,
-        domain: "team32-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team32-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 33,
-        domain: "team33-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team33-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 34,
-        domain: "talespinner.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("talespinner.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 35,
-        domain: "duofitness.live",
+        endpoints: Cow::Borrowed(&[Endpoint::website("duofitness.live")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 36,
-        domain: "team36-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team36-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 37,
-        domain: "team37-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team37-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 38,
-        domain: "team38-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team38-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 39,
-        domain: "team39-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team39-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 40,
-        domain: "drawful.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("drawful.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 41,
-        domain: "team41-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team41-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 42,
-        domain: "studysea.live",
+        endpoints: Cow::Borrowed(&[Endpoint::website("studysea.live")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 43,
-        domain: "team43-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team43-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 44,
-        domain: "roombook.bham.one",
+        endpoints: Cow::Borrowed(&[Endpoint::website("roombook.bham.one")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 45,
-        domain: "beaverbookings.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("beaverbookings.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 46,
-        domain: "speedstudy.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("speedstudy.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 47,
-        domain: "team47-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("team47-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 48,
-        domain: "reeltravel.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("reeltravel.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 49,
-        domain: "musicmatcher.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("musicmatcher.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 50,
-        domain: "teamai50-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("teamai50-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 51,
-        domain: "teamai51-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("teamai51-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 52,
-        domain: "teamai52-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("teamai52-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 53,
-        domain: "teamai53-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("teamai53-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 54,
-        domain: "teamai54-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("teamai54-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 55,
-        domain: "askit.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("askit.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 56,
-        domain: "teamai56-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("teamai56-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 57,
-        domain: "teamdai57-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("teamdai57-22.bham.team")]),
+        ..Team::DEFAULT
     },
     Team {
         team_number: 58,
-        domain: "teamdai58-22.bham.team",
+        endpoints: Cow::Borrowed(&[Endpoint::website("teamdai58-22.bham.team")]),
+        ..Team::DEFAULT
     },
 ];